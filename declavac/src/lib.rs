@@ -2,11 +2,15 @@ mod serialization;
 mod state;
 mod util;
 
-use crate::state::{CompiledState, DeclavatarState};
+use crate::{
+    serialization::Encoding,
+    state::{CompiledState, DeclavatarState, ForeignModuleResolver},
+};
 
 use std::{
     ffi::{c_char, c_void},
     ptr::null,
+    slice::{from_raw_parts, from_raw_parts_mut},
 };
 
 use declavatar::{decl_v2::DeclarationFormat, i18n::get_log_messages};
@@ -27,6 +31,25 @@ pub enum DeclavatarStatus {
     /// Error occurred in compilation.
     CompileError = 3,
 
+    /// Arbittach schema could not be loaded or conflicts with registered one.
+    SchemaError = 4,
+
+    /// Source could not be parsed.
+    ParseError = 5,
+
+    /// Imported module was not found in library paths nor module resolver.
+    ModuleNotFound = 6,
+
+    /// Source did not return avatar declaration.
+    DeclarationNotReturned = 7,
+
+    /// Declaration was loaded, but avatar could not be transformed.
+    /// Compile logs describe the reason.
+    TransformError = 8,
+
+    /// Project manifest was not found or could not be loaded, or given profile is not defined.
+    ProjectError = 9,
+
     /// Given pointer was invalid.
     InvalidPointer = 128,
 
@@ -34,6 +57,13 @@ pub enum DeclavatarStatus {
     InvalidValue = 129,
 }
 
+/// ABI version of this library.
+/// Incremented whenever exported functions or status codes are added, or their signatures change,
+/// since callers cannot use functions missing in older libraries either.
+///
+/// 2: added batch compilation, avatar encodings, project loading and `ProjectError` status.
+pub const DECLAVATAR_ABI_VERSION: u32 = 2;
+
 /// Declavatar definition file format..
 #[repr(u32)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -45,6 +75,17 @@ pub enum DeclavatarFormat {
     Lua = 2,
 }
 
+/// Encoding of compiled avatar.
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeclavatarEncoding {
+    /// JSON.
+    Json = 1,
+
+    /// MessagePack, with structs encoded as maps in the same shape as JSON.
+    MessagePack = 2,
+}
+
 /// Fetches compile log localization.
 ///
 /// # Safety
@@ -69,7 +110,31 @@ pub unsafe extern "C" fn declavatar_log_localization(
     DeclavatarStatus::Success
 }
 
+/// Fetches the version string of declavatar.
+///
+/// # Safety
+/// Given pointers must be valid.
+#[no_mangle]
+pub unsafe extern "C" fn declavatar_version(version: *mut *const c_char, version_len: *mut u32) -> DeclavatarStatus {
+    as_ref!(version, &mut *const c_char);
+    as_ref!(version_len, &mut u32);
+
+    let version_str = env!("CARGO_PKG_VERSION");
+    *version = version_str.as_ptr() as *const i8;
+    *version_len = version_str.len() as u32;
+
+    DeclavatarStatus::Success
+}
+
+/// Returns the ABI version, which callers should compare with the one they were built against.
+#[no_mangle]
+pub extern "C" fn declavatar_abi_version() -> u32 {
+    DECLAVATAR_ABI_VERSION
+}
+
 /// Initializes declavatar compiler state.
+/// The state can be moved to another thread, but must not be used from multiple threads at the same time.
+/// Use separate states or `declavatar_compile_batch` to compile in parallel.
 #[no_mangle]
 pub extern "C" fn declavatar_init() -> *mut c_void {
     let boxed = Box::new(DeclavatarState::new());
@@ -84,7 +149,8 @@ pub extern "C" fn declavatar_free(declavatar_state: *mut c_void) -> DeclavatarSt
     DeclavatarStatus::Success
 }
 
-/// Fetches last error message.
+/// Fetches the status and error message of the last operation on the state.
+/// Message is null if the last operation succeeded.
 ///
 /// # Safety
 /// Given pointer `da` must be valid.
@@ -107,7 +173,7 @@ pub unsafe extern "C" fn declavatar_last_error(
     status
 }
 
-/// Clears defined symbols/localizations/arbittach definitions.
+/// Clears defined symbols/localizations/arbittach definitions/module resolver/project entries.
 ///
 /// # Safety
 /// Given pointer `da` must be valid.
@@ -172,6 +238,36 @@ pub unsafe extern "C" fn declavatar_define_localization(
     declavatar_state.define_localization(key, value)
 }
 
+/// Sets module resolver callback, which is consulted before library paths.
+/// The callback returns `true` with `source` and `source_len` filled when the module is found.
+/// Returned source is copied before the callback is called again from the same thread.
+/// Passing null `resolver` removes current one.
+///
+/// # Safety
+/// Given pointers must be valid.
+/// `user_data` is passed to `resolver` as-is, and must be valid while the resolver is set.
+/// During `declavatar_compile_batch`, `resolver` may be called from multiple threads at once
+/// with the same `user_data`, so both of them must be thread-safe.
+#[no_mangle]
+pub unsafe extern "C" fn declavatar_set_module_resolver(
+    declavatar_state: *mut c_void,
+    resolver: Option<
+        extern "C" fn(
+            user_data: *mut c_void,
+            name: *const c_char,
+            name_len: u32,
+            source: *mut *const c_char,
+            source_len: *mut u32,
+        ) -> bool,
+    >,
+    user_data: *mut c_void,
+) -> DeclavatarStatus {
+    as_ref!(declavatar_state, &mut DeclavatarState);
+
+    let resolver = resolver.map(|r| ForeignModuleResolver::new(r, user_data));
+    declavatar_state.set_module_resolver(resolver)
+}
+
 /// Registers Arbitrary Attachment (arbittach) definition.
 ///
 /// # Safety
@@ -189,20 +285,134 @@ pub unsafe extern "C" fn declavatar_register_arbittach(
     declavatar_state.add_attachment(definition)
 }
 
+/// Registers all Arbitrary Attachment (arbittach) definitions in the directory.
+///
+/// # Safety
+/// Given pointers must be valid.
+/// `path` does not have to NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn declavatar_register_arbittach_directory(
+    declavatar_state: *mut c_void,
+    path: *const c_char,
+    path_len: u32,
+) -> DeclavatarStatus {
+    as_ref!(declavatar_state, &mut DeclavatarState);
+    as_ref!(path, &str, path_len);
+
+    declavatar_state.add_attachment_directory(path)
+}
+
+/// Loads `declavatar.toml` and applies its library paths, symbols, localizations and arbittach schemas.
+/// `path` is either the manifest itself or a file or directory in the project, in which case it is searched upward.
+/// Symbols of the profile are defined in addition if `profile` is not null.
+/// Nothing is applied if the manifest fails to load.
+///
+/// # Safety
+/// Given pointers must be valid except `profile`, which can be null.
+/// `path`, `profile` does not have to NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn declavatar_load_project(
+    declavatar_state: *mut c_void,
+    path: *const c_char,
+    path_len: u32,
+    profile: *const c_char,
+    profile_len: u32,
+) -> DeclavatarStatus {
+    as_ref!(declavatar_state, &mut DeclavatarState);
+    as_ref!(path, &str, path_len);
+    let profile = if profile.is_null() {
+        None
+    } else {
+        as_ref!(profile, &str, profile_len);
+        Some(profile)
+    };
+
+    declavatar_state.load_project(path, profile)
+}
+
+/// Retrieves the count of entry declarations in the project loaded last.
+///
+/// # Safety
+/// Given pointers must be valid.
+#[no_mangle]
+pub unsafe extern "C" fn declavatar_project_entries_count(
+    declavatar_state: *const c_void,
+    entries_count: *mut u32,
+) -> DeclavatarStatus {
+    as_ref!(declavatar_state, &DeclavatarState);
+    as_ref!(entries_count, &mut u32);
+
+    *entries_count = declavatar_state.project_entries().len() as u32;
+
+    DeclavatarStatus::Success
+}
+
+/// Retrieves the path of entry declaration in the project loaded last.
+/// Relative paths in the manifest are resolved from the directory containing it.
+///
+/// # Safety
+/// Given pointers must be valid.
+/// Returned string is not NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn declavatar_project_entry(
+    declavatar_state: *const c_void,
+    index: u32,
+    path: *mut *const c_char,
+    path_len: *mut u32,
+) -> DeclavatarStatus {
+    as_ref!(declavatar_state, &DeclavatarState);
+    as_ref!(path, &mut *const c_char);
+    as_ref!(path_len, &mut u32);
+
+    let Some(path_str) = declavatar_state.project_entries().get(index as usize) else {
+        return DeclavatarStatus::InvalidValue;
+    };
+    *path = path_str.as_ptr() as *const i8;
+    *path_len = path_str.len() as u32;
+
+    DeclavatarStatus::Success
+}
+
 /// Compiles definition with format.
+/// Compiled result is immutable, so it can be read from any thread.
 ///
 /// # Safety
 /// Given pointers must be valid.
 /// `source` does not have to NUL-terminated.
 #[no_mangle]
 pub unsafe extern "C" fn declavatar_compile(
-    declavatar_state: *const c_void,
+    declavatar_state: *mut c_void,
     compiled_state: *mut *mut c_void,
     source: *const c_char,
     source_len: u32,
     format_kind: DeclavatarFormat,
 ) -> DeclavatarStatus {
-    as_ref!(declavatar_state, &DeclavatarState);
+    declavatar_compile_with_encoding(
+        declavatar_state,
+        compiled_state,
+        source,
+        source_len,
+        format_kind,
+        DeclavatarEncoding::Json,
+    )
+}
+
+/// Compiles definition with format, encoding the avatar in given encoding.
+/// Use `declavatar_compiled_avatar_binary` to retrieve the avatar encoded in other than JSON.
+///
+/// # Safety
+/// Given pointers must be valid.
+/// `source` does not have to NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn declavatar_compile_with_encoding(
+    declavatar_state: *mut c_void,
+    compiled_state: *mut *mut c_void,
+    source: *const c_char,
+    source_len: u32,
+    format_kind: DeclavatarFormat,
+    encoding_kind: DeclavatarEncoding,
+) -> DeclavatarStatus {
+    as_ref!(declavatar_state, &mut DeclavatarState);
     as_ref!(compiled_state, &mut *mut CompiledState);
     as_ref!(source, &str, source_len);
 
@@ -212,12 +422,104 @@ pub unsafe extern "C" fn declavatar_compile(
         DeclavatarFormat::Lua => DeclarationFormat::Lua,
         _ => return DeclavatarStatus::InvalidValue,
     };
+    #[allow(unreachable_patterns)]
+    let encoding = match encoding_kind {
+        DeclavatarEncoding::Json => Encoding::Json,
+        DeclavatarEncoding::MessagePack => Encoding::MessagePack,
+        _ => return DeclavatarStatus::InvalidValue,
+    };
 
-    let (compiled, status) = declavatar_state.compile(source, format);
+    let (compiled, status) = declavatar_state.compile(source, format, encoding);
     *compiled_state = Box::into_raw(Box::new(compiled));
     status
 }
 
+/// Compiles multiple definitions concurrently with format.
+/// `compiled_states` and `statuses` receive `count` results in the same order as `sources`.
+/// Each compiled result must be freed by `declavatar_compiled_free`.
+/// Returns the status of the first failed source, or `Success` if all succeeded.
+/// Sources handled by a panicked compile thread fail with `CompileError`.
+///
+/// # Safety
+/// Given pointers must be valid.
+/// `sources` and `source_lens` must have `count` elements, and each source does not have to NUL-terminated.
+/// `compiled_states` and `statuses` must have room for `count` elements.
+#[no_mangle]
+pub unsafe extern "C" fn declavatar_compile_batch(
+    declavatar_state: *mut c_void,
+    compiled_states: *mut *mut c_void,
+    statuses: *mut DeclavatarStatus,
+    sources: *const *const c_char,
+    source_lens: *const u32,
+    count: u32,
+    format_kind: DeclavatarFormat,
+) -> DeclavatarStatus {
+    declavatar_compile_batch_with_encoding(
+        declavatar_state,
+        compiled_states,
+        statuses,
+        sources,
+        source_lens,
+        count,
+        format_kind,
+        DeclavatarEncoding::Json,
+    )
+}
+
+/// Compiles multiple definitions concurrently with format, encoding the avatars in given encoding.
+/// Works as `declavatar_compile_batch` otherwise.
+///
+/// # Safety
+/// Same as `declavatar_compile_batch`.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn declavatar_compile_batch_with_encoding(
+    declavatar_state: *mut c_void,
+    compiled_states: *mut *mut c_void,
+    statuses: *mut DeclavatarStatus,
+    sources: *const *const c_char,
+    source_lens: *const u32,
+    count: u32,
+    format_kind: DeclavatarFormat,
+    encoding_kind: DeclavatarEncoding,
+) -> DeclavatarStatus {
+    as_ref!(declavatar_state, &mut DeclavatarState);
+    if compiled_states.is_null() || statuses.is_null() || sources.is_null() || source_lens.is_null() {
+        return DeclavatarStatus::InvalidPointer;
+    }
+
+    #[allow(unreachable_patterns)]
+    let format = match format_kind {
+        DeclavatarFormat::Sexpr => DeclarationFormat::Sexpr,
+        DeclavatarFormat::Lua => DeclarationFormat::Lua,
+        _ => return DeclavatarStatus::InvalidValue,
+    };
+    #[allow(unreachable_patterns)]
+    let encoding = match encoding_kind {
+        DeclavatarEncoding::Json => Encoding::Json,
+        DeclavatarEncoding::MessagePack => Encoding::MessagePack,
+        _ => return DeclavatarStatus::InvalidValue,
+    };
+
+    let sources = from_raw_parts(sources, count as usize);
+    let source_lens = from_raw_parts(source_lens, count as usize);
+    let mut source_strs = Vec::with_capacity(sources.len());
+    for (&source, &source_len) in sources.iter().zip(source_lens) {
+        as_ref!(source, &str, source_len);
+        source_strs.push(source);
+    }
+
+    let results = declavatar_state.compile_batch(&source_strs, format, encoding);
+    let compiled_states = from_raw_parts_mut(compiled_states as *mut *mut CompiledState, count as usize);
+    let statuses = from_raw_parts_mut(statuses, count as usize);
+    for (i, (compiled, status)) in results.into_iter().enumerate() {
+        compiled_states[i] = Box::into_raw(Box::new(compiled));
+        statuses[i] = status;
+    }
+
+    declavatar_state.last_error().1
+}
+
 /// Frees compiled result.
 ///
 /// # Safety
@@ -230,6 +532,7 @@ pub unsafe extern "C" fn declavatar_compiled_free(compiled_state: *mut c_void) -
 }
 
 /// Retrieves the pointer of compiled JSON string.
+/// It is null if the avatar was compiled with other encoding.
 ///
 /// # Safety
 /// Given pointer must be valid.
@@ -255,6 +558,59 @@ pub unsafe extern "C" fn declavatar_compiled_avatar_json(
     DeclavatarStatus::Success
 }
 
+/// Retrieves the pointer of compiled avatar data in the encoding given on compilation.
+/// It is null if the avatar failed to compile.
+///
+/// # Safety
+/// Given pointers must be valid.
+#[no_mangle]
+pub unsafe extern "C" fn declavatar_compiled_avatar_binary(
+    compiled_state: *const c_void,
+    data: *mut *const u8,
+    data_len: *mut u32,
+) -> DeclavatarStatus {
+    as_ref!(compiled_state, &CompiledState);
+    as_ref!(data, &mut *const u8);
+    as_ref!(data_len, &mut u32);
+
+    if let Some(bytes) = compiled_state.avatar_bytes() {
+        *data = bytes.as_ptr();
+        *data_len = bytes.len() as u32;
+    } else {
+        *data = null();
+        *data_len = 0;
+    }
+
+    DeclavatarStatus::Success
+}
+
+/// Retrieves the pointer of intermediate declaration JSON string.
+/// It is available even if the avatar failed to compile, unless the declaration itself failed to load.
+///
+/// # Safety
+/// Given pointer must be valid.
+/// Returned string is not NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn declavatar_compiled_declaration_json(
+    compiled_state: *const c_void,
+    json_string: *mut *const c_char,
+    json_string_len: *mut u32,
+) -> DeclavatarStatus {
+    as_ref!(compiled_state, &CompiledState);
+    as_ref!(json_string, &mut *const c_char);
+    as_ref!(json_string_len, &mut u32);
+
+    if let Some(json_str) = compiled_state.declaration_json() {
+        *json_string = json_str.as_ptr() as *const i8;
+        *json_string_len = json_str.len() as u32;
+    } else {
+        *json_string = null();
+        *json_string_len = 0;
+    }
+
+    DeclavatarStatus::Success
+}
+
 /// Retrieves the count of compile logs.
 ///
 /// # Safety
@@ -1,3 +1,10 @@
+use std::{
+    error::Error,
+    fmt::{Display, Formatter, Result as FmtResult},
+    str::from_utf8,
+};
+
+use rmp_serde::encode::Error as RmpEncodeError;
 use serde::Serialize;
 use serde_json::Error as SerdeJsonError;
 
@@ -34,3 +41,97 @@ impl<T: Serialize> Jsoned<T> {
         Ok(())
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Json,
+    MessagePack,
+}
+
+#[derive(Debug)]
+pub enum EncodeError {
+    Json(SerdeJsonError),
+    MessagePack(RmpEncodeError),
+}
+
+impl Display for EncodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            EncodeError::Json(err) => write!(f, "{err}"),
+            EncodeError::MessagePack(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl Error for EncodeError {}
+
+// same data model as JSON; structs are encoded as maps with field names
+#[derive(Debug, Clone)]
+pub struct Encoded<T> {
+    data: T,
+    encoding: Encoding,
+    serialized: Vec<u8>,
+}
+
+#[allow(dead_code)]
+impl<T: Serialize> Encoded<T> {
+    pub fn new(data: T, encoding: Encoding) -> Result<Encoded<T>, EncodeError> {
+        let serialized = match encoding {
+            Encoding::Json => serde_json::to_vec(&data).map_err(EncodeError::Json)?,
+            Encoding::MessagePack => rmp_serde::to_vec_named(&data).map_err(EncodeError::MessagePack)?,
+        };
+        Ok(Encoded {
+            data,
+            encoding,
+            serialized,
+        })
+    }
+
+    pub fn data(&self) -> &T {
+        &self.data
+    }
+
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.serialized
+    }
+
+    pub fn json(&self) -> Option<&str> {
+        match self.encoding {
+            Encoding::Json => Some(from_utf8(&self.serialized).expect("JSON should be valid UTF-8")),
+            Encoding::MessagePack => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::serialization::{Encoded, Encoding};
+
+    use declavatar::{
+        avatar_v2::Transformer,
+        decl_v2::{compile_declaration, Arguments, DeclarationFormat},
+    };
+    use serde_json::Value;
+
+    #[test]
+    fn encodes_same_data_model_as_json() {
+        let source = include_str!("../../examples/sexpr/basic.declisp");
+        let decl_avatar =
+            compile_declaration(source, DeclarationFormat::Sexpr, Arguments::new()).expect("failed to load");
+        let avatar = Transformer::new()
+            .transform_avatar(decl_avatar)
+            .avatar
+            .expect("avatar should be compiled");
+
+        let json = Encoded::new(avatar.clone(), Encoding::Json).expect("should be encoded");
+        let message_pack = Encoded::new(avatar, Encoding::MessagePack).expect("should be encoded");
+        let from_json: Value = serde_json::from_slice(json.bytes()).expect("should be decoded");
+        let from_message_pack: Value = rmp_serde::from_slice(message_pack.bytes()).expect("should be decoded");
+        assert_eq!(from_message_pack, from_json);
+        assert_eq!(message_pack.json(), None);
+    }
+}
@@ -1,109 +1,222 @@
-use crate::{serialization::Jsoned, DeclavatarStatus};
+use crate::{
+    serialization::{Encoded, Encoding, Jsoned},
+    DeclavatarStatus,
+};
+
+use std::{
+    any::Any,
+    ffi::{c_char, c_void},
+    iter::repeat_with,
+    ptr::null,
+    slice::from_raw_parts,
+    str::from_utf8,
+    thread::{available_parallelism, scope},
+};
 
 use declavatar::{
     avatar_v2::{
         data::{attachment::schema::Attachment, avatar::Avatar},
         Transformer,
     },
-    decl_v2::{compile_declaration, Arguments, DeclarationFormat},
+    decl_v2::{
+        compile_declaration, data::avatar::DeclAvatar, error::DeclError, Arguments, DeclarationFormat, ModuleResolver,
+    },
     log::{Log, SerializedLog},
+    project::{error::ProjectError, Project},
+};
+
+// handles are moved across threads by callers, so these must not contain Rc or RefCell
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<DeclavatarState>();
+    assert_send_sync::<CompiledState>();
 };
 
 #[derive(Debug, Clone)]
 pub struct DeclavatarState {
     args: Arguments,
-    attachments: Vec<Attachment>,
+    transformer: Transformer,
+    project_entries: Vec<String>,
     last_error: Option<String>,
+    last_status: DeclavatarStatus,
 }
 
 impl DeclavatarState {
     pub fn new() -> DeclavatarState {
         DeclavatarState {
             args: Arguments::new(),
-            attachments: vec![],
+            transformer: Transformer::new(),
+            project_entries: vec![],
             last_error: None,
+            last_status: DeclavatarStatus::Success,
         }
     }
 
     pub fn last_error(&self) -> (Option<&str>, DeclavatarStatus) {
-        (self.last_error.as_deref(), DeclavatarStatus::Success)
+        (self.last_error.as_deref(), self.last_status)
+    }
+
+    fn succeed(&mut self) -> DeclavatarStatus {
+        self.last_error = None;
+        self.last_status = DeclavatarStatus::Success;
+        DeclavatarStatus::Success
+    }
+
+    fn fail(&mut self, status: DeclavatarStatus, message: impl Into<String>) -> DeclavatarStatus {
+        self.last_error = Some(message.into());
+        self.last_status = status;
+        status
     }
 
     pub fn clear(&mut self) -> DeclavatarStatus {
         self.args.clear();
-        self.attachments.clear();
+        self.transformer = Transformer::new();
+        self.project_entries.clear();
 
-        self.last_error = None;
-        DeclavatarStatus::Success
+        self.succeed()
     }
 
     pub fn add_library_path(&mut self, path: &str) -> DeclavatarStatus {
         self.args.add_library_path(path);
 
-        self.last_error = None;
-        DeclavatarStatus::Success
+        self.succeed()
     }
 
     pub fn define_symbol(&mut self, symbol: &str) -> DeclavatarStatus {
         self.args.define_symbol(symbol);
 
-        self.last_error = None;
-        DeclavatarStatus::Success
+        self.succeed()
     }
 
     pub fn define_localization(&mut self, key: &str, value: &str) -> DeclavatarStatus {
         self.args.define_localization(key, value);
 
-        self.last_error = None;
-        DeclavatarStatus::Success
+        self.succeed()
+    }
+
+    pub fn set_module_resolver(&mut self, resolver: Option<ForeignModuleResolver>) -> DeclavatarStatus {
+        let resolver = resolver.map(|r| ModuleResolver::new(move |name| r.resolve(name)));
+        self.args.set_module_resolver(resolver);
+
+        self.succeed()
     }
 
     pub fn add_attachment(&mut self, schema_json: &str) -> DeclavatarStatus {
         let schema = match serde_json::from_str::<Attachment>(schema_json) {
             Ok(schema) => schema,
-            Err(err) => {
-                self.last_error = Some(err.to_string());
-                return DeclavatarStatus::JsonError;
-            }
+            Err(err) => return self.fail(DeclavatarStatus::JsonError, err.to_string()),
         };
-        self.attachments.push(schema);
+        if let Err(err) = self.transformer.register_arbittach_schema(schema) {
+            return self.fail(DeclavatarStatus::SchemaError, err.to_string());
+        }
 
-        self.last_error = None;
-        DeclavatarStatus::Success
+        self.succeed()
+    }
+
+    pub fn add_attachment_directory(&mut self, path: &str) -> DeclavatarStatus {
+        if let Err(err) = self.transformer.load_arbittach_schemas(path) {
+            return self.fail(DeclavatarStatus::SchemaError, err.to_string());
+        }
+
+        self.succeed()
+    }
+
+    // applied to copies first, so that a failure leaves the state untouched
+    pub fn load_project(&mut self, path: &str, profile: Option<&str>) -> DeclavatarStatus {
+        let mut args = self.args.clone();
+        let mut transformer = self.transformer.clone();
+        let loaded = Project::find(path).and_then(|project| {
+            project.configure_arguments(&mut args, profile)?;
+            project.register_attachment_schemas(&mut transformer)?;
+            Ok(project)
+        });
+        let project = match loaded {
+            Ok(project) => project,
+            Err(ProjectError::Schema(err)) => return self.fail(DeclavatarStatus::SchemaError, err.to_string()),
+            Err(err) => return self.fail(DeclavatarStatus::ProjectError, err.to_string()),
+        };
+
+        self.args = args;
+        self.transformer = transformer;
+        self.project_entries = project
+            .entries()
+            .iter()
+            .map(|entry| entry.to_string_lossy().to_string())
+            .collect();
+        self.succeed()
+    }
+
+    pub fn project_entries(&self) -> &[String] {
+        &self.project_entries
     }
 
-    pub fn compile(&self, source: &str, format: DeclarationFormat) -> (CompiledState, DeclavatarStatus) {
-        let decl_avatar = match compile_declaration(source, format, self.args.clone()) {
-            Ok(avatar) => avatar,
-            Err(err) => {
-                let log = Jsoned::new(err.serialize_log([])).expect("should be serialized");
-                return (
-                    CompiledState {
-                        avatar: None,
-                        logs: vec![log],
-                    },
-                    DeclavatarStatus::CompileError,
-                );
-            }
+    pub fn compile(
+        &mut self,
+        source: &str,
+        format: DeclarationFormat,
+        encoding: Encoding,
+    ) -> (CompiledState, DeclavatarStatus) {
+        let (compiled, status, message) = compile(&self.args, &self.transformer, source, format, encoding);
+        match message {
+            Some(message) => self.fail(status, message),
+            None => self.succeed(),
         };
+        (compiled, status)
+    }
 
-        let mut transformer = Transformer::new();
-        for attachment in &self.attachments {
-            transformer.register_arbittach_schema(attachment.clone());
+    pub fn compile_batch(
+        &mut self,
+        sources: &[&str],
+        format: DeclarationFormat,
+        encoding: Encoding,
+    ) -> Vec<(CompiledState, DeclavatarStatus)> {
+        if sources.is_empty() {
+            self.succeed();
+            return vec![];
         }
-        let transformed = transformer.transform_avatar(decl_avatar);
-        let avatar = transformed
-            .avatar
-            .map(Jsoned::new)
-            .transpose()
-            .expect("should be serialized");
-        let logs = transformed
-            .logs
+
+        let workers = available_parallelism().map(|n| n.get()).unwrap_or(1).min(sources.len());
+        let chunk_size = sources.len().div_ceil(workers);
+        let (args, transformer) = (&self.args, &self.transformer);
+        let results: Vec<_> = scope(|s| {
+            let handles: Vec<_> = sources
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    let handle = s.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|source| compile(args, transformer, source, format, encoding))
+                            .collect::<Vec<_>>()
+                    });
+                    (chunk.len(), handle)
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|(chunk_len, h)| match h.join() {
+                    Ok(results) => results,
+                    // every source in the chunk fails, but other chunks are kept
+                    Err(payload) => {
+                        let message = panic_message(payload.as_ref());
+                        repeat_with(|| panicked(message)).take(chunk_len).collect()
+                    }
+                })
+                .collect()
+        });
+
+        // reports the first failure; each status is returned separately
+        let first_failure = results
+            .iter()
+            .enumerate()
+            .find_map(|(i, (_, status, message))| message.as_ref().map(|m| (i, *status, m.clone())));
+        match first_failure {
+            Some((index, status, message)) => self.fail(status, format!("source #{index}: {message}")),
+            None => self.succeed(),
+        };
+        results
             .into_iter()
-            .map(Jsoned::new)
-            .collect::<Result<Vec<_>, _>>()
-            .expect("should be serialized");
-        (CompiledState { avatar, logs }, DeclavatarStatus::Success)
+            .map(|(compiled, status, _)| (compiled, status))
+            .collect()
     }
 }
 
@@ -113,15 +226,155 @@ impl Default for DeclavatarState {
     }
 }
 
+pub type ModuleResolverCallback = extern "C" fn(
+    user_data: *mut c_void,
+    name: *const c_char,
+    name_len: u32,
+    source: *mut *const c_char,
+    source_len: *mut u32,
+) -> bool;
+
+// caller guarantees that user_data can be used from any thread while the resolver is set
+pub struct ForeignModuleResolver {
+    callback: ModuleResolverCallback,
+    user_data: *mut c_void,
+}
+
+unsafe impl Send for ForeignModuleResolver {}
+unsafe impl Sync for ForeignModuleResolver {}
+
+impl ForeignModuleResolver {
+    pub fn new(callback: ModuleResolverCallback, user_data: *mut c_void) -> ForeignModuleResolver {
+        ForeignModuleResolver { callback, user_data }
+    }
+
+    fn resolve(&self, name: &str) -> Option<String> {
+        let mut source = null();
+        let mut source_len = 0;
+        let found = (self.callback)(
+            self.user_data,
+            name.as_ptr() as *const c_char,
+            name.len() as u32,
+            &mut source,
+            &mut source_len,
+        );
+        if !found || source.is_null() {
+            return None;
+        }
+
+        // source is copied here, so the caller can release it after returning
+        let slice = unsafe { from_raw_parts(source as *const u8, source_len as usize) };
+        from_utf8(slice).ok().map(|s| s.to_string())
+    }
+}
+
+// does not touch any state, so that it can be called from multiple threads
+pub fn compile(
+    args: &Arguments,
+    transformer: &Transformer,
+    source: &str,
+    format: DeclarationFormat,
+    encoding: Encoding,
+) -> (CompiledState, DeclavatarStatus, Option<String>) {
+    let decl_avatar = match compile_declaration(source, format, args.clone()) {
+        Ok(avatar) => avatar,
+        Err(err) => {
+            let status = match &err {
+                DeclError::ParseError(_) => DeclavatarStatus::ParseError,
+                DeclError::ModuleNotFound(_) => DeclavatarStatus::ModuleNotFound,
+                DeclError::DelclarationNotReturned(_) => DeclavatarStatus::DeclarationNotReturned,
+                _ => DeclavatarStatus::CompileError,
+            };
+            let message = err.to_string();
+            let log = Jsoned::new(err.serialize_log([])).expect("should be serialized");
+            return (
+                CompiledState {
+                    declaration: None,
+                    avatar: None,
+                    logs: vec![log],
+                },
+                status,
+                Some(message),
+            );
+        }
+    };
+
+    // kept for inspection even if transformation fails
+    let declaration = Jsoned::new(decl_avatar.clone()).expect("should be serialized");
+    let transformed = transformer.transform_avatar(decl_avatar);
+    let avatar = transformed
+        .avatar
+        .map(|avatar| Encoded::new(avatar, encoding))
+        .transpose()
+        .expect("should be serialized");
+    let logs = transformed
+        .logs
+        .into_iter()
+        .map(Jsoned::new)
+        .collect::<Result<Vec<_>, _>>()
+        .expect("should be serialized");
+    let (status, message) = if avatar.is_some() {
+        (DeclavatarStatus::Success, None)
+    } else {
+        (
+            DeclavatarStatus::TransformError,
+            Some(format!("failed to transform avatar ({} logs emitted)", logs.len())),
+        )
+    };
+    (
+        CompiledState {
+            declaration: Some(declaration),
+            avatar,
+            logs,
+        },
+        status,
+        message,
+    )
+}
+
+fn panicked(message: &str) -> (CompiledState, DeclavatarStatus, Option<String>) {
+    (
+        CompiledState {
+            declaration: None,
+            avatar: None,
+            logs: vec![],
+        },
+        DeclavatarStatus::CompileError,
+        Some(format!("compile thread panicked: {message}")),
+    )
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown panic"
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct CompiledState {
-    avatar: Option<Jsoned<Avatar>>,
+    declaration: Option<Jsoned<DeclAvatar>>,
+    avatar: Option<Encoded<Avatar>>,
     logs: Vec<Jsoned<SerializedLog>>,
 }
 
 impl CompiledState {
+    pub fn declaration_json(&self) -> Option<&str> {
+        self.declaration
+            .as_ref()
+            .map(|d| d.json().expect("should be serialized"))
+    }
+
+    // None if encoded in other format
     pub fn avatar_json(&self) -> Option<&str> {
-        self.avatar.as_ref().map(|a| a.json().expect("should be serialized"))
+        self.avatar.as_ref().and_then(|a| a.json())
+    }
+
+    pub fn avatar_bytes(&self) -> Option<&[u8]> {
+        self.avatar.as_ref().map(|a| a.bytes())
     }
 
     pub fn logs_len(&self) -> usize {
@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 
 /// Declavatar CLI
 #[derive(Debug, Clone, Parser)]
@@ -20,17 +20,134 @@ pub enum Subcommand {
         #[clap(flatten)]
         file_option: FileOption,
 
-        /// Registers an Arbitrary Attachment schema.
+        /// Registers an Arbitrary Attachment schema file, or all schemas in a directory.
         #[clap(short = 'A', long = "attachment-schema")]
         arbittach_schema_files: Vec<PathBuf>,
+
+        /// Watches the file, modules, libraries, schemas and project manifest, and shows changes on every recompilation.
+        #[clap(short, long)]
+        watch: bool,
+
+        /// Output format of diagnostics.
+        #[clap(long, value_enum, default_value_t = DiagnosticsFormat::Text, conflicts_with = "watch")]
+        diagnostics_format: DiagnosticsFormat,
+    },
+
+    /// Compiles declaration file and renders FX controller layers as a graph.
+    Graph {
+        #[clap(flatten)]
+        file_option: FileOption,
+
+        /// Registers an Arbitrary Attachment schema file, or all schemas in a directory.
+        #[clap(short = 'A', long = "attachment-schema")]
+        arbittach_schema_files: Vec<PathBuf>,
+
+        /// Output format of the graph.
+        #[clap(short, long, value_enum, default_value_t = GraphFormat::Dot)]
+        format: GraphFormat,
+
+        /// Renders only the layers with given names.
+        #[clap(long = "layer")]
+        layers: Vec<String>,
+    },
+
+    /// Compiles declaration file and shows where each parameter is written and read.
+    Xref {
+        #[clap(flatten)]
+        file_option: FileOption,
+
+        /// Registers an Arbitrary Attachment schema file, or all schemas in a directory.
+        #[clap(short = 'A', long = "attachment-schema")]
+        arbittach_schema_files: Vec<PathBuf>,
+
+        /// Shows only the parameters with given names.
+        #[clap(short, long = "parameter")]
+        parameters: Vec<String>,
+
+        /// Prints the report as JSON.
+        #[clap(long)]
+        json: bool,
+    },
+
+    /// Compiles two declaration files and shows semantic differences from the first one to the second one.
+    /// Files with .json extension are read as compiled avatars instead.
+    Diff {
+        #[clap(flatten)]
+        file_option: FileOption,
+
+        /// Filename of the newer declaration, compiled with the same options.
+        new_file: PathBuf,
+
+        /// Registers an Arbitrary Attachment schema file, or all schemas in a directory.
+        #[clap(short = 'A', long = "attachment-schema")]
+        arbittach_schema_files: Vec<PathBuf>,
+
+        /// Prints the report as JSON.
+        #[clap(long)]
+        json: bool,
+    },
+
+    /// Compiles declaration file into several variants and shows items which differ between them.
+    /// All profiles in the project manifest are compiled if no variant is given.
+    Variants {
+        #[clap(flatten)]
+        file_option: FileOption,
+
+        /// Registers an Arbitrary Attachment schema file, or all schemas in a directory.
+        #[clap(short = 'A', long = "attachment-schema")]
+        arbittach_schema_files: Vec<PathBuf>,
+
+        /// Compiles a variant with the profile of the same name, or with additional symbols given as `name:symbol,...`.
+        #[clap(short = 'V', long = "variant", value_parser = parse_variant)]
+        variants: Vec<VariantOption>,
+
+        /// Writes each compiled avatar into the directory as `<variant>.json`.
+        #[clap(short, long)]
+        output_directory: Option<PathBuf>,
+
+        /// Prints the report as JSON.
+        #[clap(long)]
+        json: bool,
+    },
+
+    /// Prints the JSON Schema of compiled avatars.
+    Schema {
+        /// Shows the schema in indented form.
+        #[clap(short, long)]
+        indented: bool,
     },
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DiagnosticsFormat {
+    /// Localized text; the avatar JSON is printed instead if compiled.
+    Text,
+
+    /// JSON object containing the avatar and diagnostics.
+    Json,
+
+    /// SARIF 2.1.0 log.
+    Sarif,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum GraphFormat {
+    /// Graphviz DOT.
+    Dot,
+
+    /// Mermaid flowchart.
+    Mermaid,
+}
+
 #[derive(Debug, Clone, Parser)]
 pub struct FileOption {
     /// Filename.
     pub file: PathBuf,
 
+    /// Merges a module declaration into the avatar.
+    #[clap(short = 'M', long = "module")]
+    pub modules: Vec<PathBuf>,
+
     /// Shows the result struct in indented form.
     #[clap(short, long)]
     pub indented: bool,
@@ -46,6 +163,52 @@ pub struct FileOption {
     /// Defines a localization pair.
     #[clap(short = 'l', long = "localize", value_parser = parse_localization_pair)]
     pub localizations: Vec<(String, String)>,
+
+    /// Applies a build profile defined in the project manifest.
+    #[clap(short = 'P', long)]
+    pub profile: Option<String>,
+
+    /// Ignores declavatar.toml found in the directories containing the file.
+    #[clap(long, conflicts_with = "profile")]
+    pub no_project: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct VariantOption {
+    pub name: String,
+
+    // None for the profile of the same name
+    pub symbols: Option<Vec<String>>,
+}
+
+fn parse_variant(s: &str) -> Result<VariantOption, String> {
+    let (name, symbols) = match s.split_once(':') {
+        Some((name, symbols)) => {
+            let symbols = symbols
+                .split(',')
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string())
+                .collect();
+            (name, Some(symbols))
+        }
+        None => (s, None),
+    };
+    if name.is_empty() {
+        return Err(format!("invalid variant definition: {s}"));
+    }
+    validate_variant_name(name)?;
+    Ok(VariantOption {
+        name: name.to_string(),
+        symbols,
+    })
+}
+
+// variant names become file names of compiled avatars
+pub fn validate_variant_name(name: &str) -> Result<(), String> {
+    if name.contains(['/', '\\']) || name.contains("..") {
+        return Err(format!("variant name must not contain path separators or '..': {name}"));
+    }
+    Ok(())
 }
 
 fn parse_localization_pair(s: &str) -> Result<(String, String), String> {
@@ -55,3 +218,23 @@ fn parse_localization_pair(s: &str) -> Result<(String, String), String> {
         Err(format!("invalid localization pair definition: {s}"))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::application::parse_variant;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_variant_definitions() {
+        let variant = parse_variant("quest:QUEST,,LOW").expect("should parse");
+        assert_eq!(variant.name, "quest");
+        assert_eq!(variant.symbols, Some(vec!["QUEST".to_string(), "LOW".to_string()]));
+        assert_eq!(parse_variant("pc").expect("should parse").symbols, None);
+
+        // names are used as output file names
+        assert!(parse_variant(":QUEST").is_err());
+        assert!(parse_variant("../pc").is_err());
+        assert!(parse_variant(r"variants\pc:PC").is_err());
+    }
+}
@@ -0,0 +1,293 @@
+use crate::{application::DiagnosticsFormat, I18nLog};
+
+use std::{collections::BTreeMap, path::Path, process::ExitCode};
+
+use anyhow::{Error, Result};
+use declavatar::{
+    avatar_v2::{data::avatar::Avatar, TransformResult},
+    decl_v2::error::DeclError,
+    log::{Log, SerializedLog, Severity},
+};
+use serde_json::{json, Value};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Exit code when the avatar compiled.
+pub const EXIT_SUCCESS: u8 = 0;
+
+/// Exit code when the declaration was loaded, but compilation emitted errors.
+pub const EXIT_DIAGNOSTICS: u8 = 1;
+
+/// Exit code when the declaration or schemas could not be loaded.
+pub const EXIT_LOAD_FAILURE: u8 = 2;
+
+pub enum CompileOutcome {
+    Compiled(Box<TransformResult>),
+    LoadFailed(Error),
+}
+
+impl CompileOutcome {
+    pub fn new(result: Result<TransformResult>) -> CompileOutcome {
+        match result {
+            Ok(result) => CompileOutcome::Compiled(Box::new(result)),
+            Err(e) => CompileOutcome::LoadFailed(e),
+        }
+    }
+
+    pub fn avatar(&self) -> Option<&Avatar> {
+        match self {
+            CompileOutcome::Compiled(result) => result.avatar.as_ref(),
+            CompileOutcome::LoadFailed(_) => None,
+        }
+    }
+
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            CompileOutcome::Compiled(result) if result.avatar.is_some() => ExitCode::from(EXIT_SUCCESS),
+            CompileOutcome::Compiled(_) => ExitCode::from(EXIT_DIAGNOSTICS),
+            CompileOutcome::LoadFailed(_) => ExitCode::from(EXIT_LOAD_FAILURE),
+        }
+    }
+
+    // load errors from declaration are also reported as diagnostics
+    fn logs(&self) -> Vec<SerializedLog> {
+        match self {
+            CompileOutcome::Compiled(result) => result.logs.clone(),
+            CompileOutcome::LoadFailed(err) => match err.downcast_ref::<DeclError>() {
+                Some(decl_err) => vec![decl_err.serialize_log([])],
+                None => vec![],
+            },
+        }
+    }
+}
+
+pub fn print_diagnostics(
+    outcome: &CompileOutcome,
+    format: DiagnosticsFormat,
+    file: &Path,
+    indented: bool,
+) -> Result<()> {
+    let i18n_log = I18nLog::load_current_locale();
+    let document = match format {
+        DiagnosticsFormat::Text => return print_text(outcome, &i18n_log, indented),
+        DiagnosticsFormat::Json => construct_json(outcome, &i18n_log)?,
+        DiagnosticsFormat::Sarif => construct_sarif(outcome, &i18n_log, file),
+    };
+
+    let json = if indented {
+        serde_json::to_string_pretty(&document)
+    } else {
+        serde_json::to_string(&document)
+    }?;
+    println!("{json}");
+    Ok(())
+}
+
+fn print_text(outcome: &CompileOutcome, i18n_log: &I18nLog, indented: bool) -> Result<()> {
+    if let Some(avatar) = outcome.avatar() {
+        let json = if indented {
+            serde_json::to_string_pretty(avatar)
+        } else {
+            serde_json::to_string(avatar)
+        }?;
+        println!("{json}");
+        return Ok(());
+    }
+
+    match outcome {
+        CompileOutcome::Compiled(result) => {
+            for log in &result.logs {
+                let message = i18n_log.localize(log.kind.clone(), log.args.clone());
+                println!("{:?}: {message}", log.severity);
+                for ctx in &log.context {
+                    println!("@ {ctx}");
+                }
+                println!();
+            }
+        }
+        CompileOutcome::LoadFailed(err) => {
+            eprintln!("Error: {err}");
+        }
+    }
+    Ok(())
+}
+
+fn construct_json(outcome: &CompileOutcome, i18n_log: &I18nLog) -> Result<Value> {
+    let diagnostics: Vec<_> = outcome
+        .logs()
+        .into_iter()
+        .map(|log| {
+            let message = i18n_log.localize(log.kind.clone(), log.args.clone());
+            json!({
+                "severity": log.severity,
+                "kind": log.kind,
+                "args": log.args,
+                "context": log.context,
+                "message": message,
+            })
+        })
+        .collect();
+
+    let (avatar, error) = match outcome {
+        CompileOutcome::Compiled(result) => (serde_json::to_value(&result.avatar)?, Value::Null),
+        CompileOutcome::LoadFailed(err) => (Value::Null, Value::String(err.to_string())),
+    };
+    Ok(json!({
+        "success": outcome.avatar().is_some(),
+        "avatar": avatar,
+        "error": error,
+        "diagnostics": diagnostics,
+    }))
+}
+
+fn construct_sarif(outcome: &CompileOutcome, i18n_log: &I18nLog, file: &Path) -> Value {
+    let uri = file.to_string_lossy().replace('\\', "/");
+    let logs = outcome.logs();
+
+    let rules: BTreeMap<_, _> = logs
+        .iter()
+        .map(|log| {
+            let rule = json!({
+                "id": log.kind,
+                "shortDescription": { "text": i18n_log.title(&log.kind) },
+            });
+            (log.kind.clone(), rule)
+        })
+        .collect();
+    let results: Vec<_> = logs
+        .into_iter()
+        .map(|log| {
+            let level = match log.severity {
+                Severity::Information => "note",
+                Severity::Warning => "warning",
+                Severity::Error => "error",
+            };
+            let message = i18n_log.localize(log.kind.clone(), log.args.clone());
+            // contexts do not have line information, so they are attached as logical locations
+            let logical_locations: Vec<_> = log
+                .context
+                .iter()
+                .map(|ctx| json!({ "fullyQualifiedName": ctx }))
+                .collect();
+            json!({
+                "ruleId": log.kind,
+                "level": level,
+                "message": { "text": message },
+                "locations": [{
+                    "physicalLocation": { "artifactLocation": { "uri": uri } },
+                    "logicalLocations": logical_locations,
+                }],
+                "properties": {
+                    "severity": log.severity,
+                    "args": log.args,
+                    "context": log.context,
+                },
+            })
+        })
+        .collect();
+
+    let notifications: Vec<_> = match outcome {
+        CompileOutcome::LoadFailed(err) => vec![json!({
+            "level": "error",
+            "message": { "text": err.to_string() },
+        })],
+        CompileOutcome::Compiled(_) => vec![],
+    };
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules.into_values().collect::<Vec<_>>(),
+                },
+            },
+            "invocations": [{
+                "executionSuccessful": !matches!(outcome, CompileOutcome::LoadFailed(_)),
+                "toolExecutionNotifications": notifications,
+            }],
+            "results": results,
+        }],
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        diagnostics::{construct_json, construct_sarif, CompileOutcome},
+        I18nLog,
+    };
+
+    use std::path::Path;
+
+    use anyhow::anyhow;
+    use declavatar::decl_v2::error::DeclError;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn i18n_log() -> I18nLog {
+        let localization = [
+            ("decl.module_not_found", "Declaration Error"),
+            ("decl.module_not_found:description", "module '{0}' not found"),
+        ];
+        I18nLog {
+            localization: localization
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn reports_load_failure_in_sarif() {
+        let outcome = CompileOutcome::LoadFailed(DeclError::ModuleNotFound("hat".to_string()).into());
+        let sarif = construct_sarif(&outcome, &i18n_log(), Path::new(r"avatars\base.declisp"));
+
+        let run = &sarif["runs"][0];
+        assert_eq!(
+            run["invocations"],
+            json!([{
+                "executionSuccessful": false,
+                "toolExecutionNotifications": [{
+                    "level": "error",
+                    "message": { "text": "module hat not found" },
+                }],
+            }])
+        );
+        assert_eq!(
+            run["tool"]["driver"]["rules"],
+            json!([{
+                "id": "decl.module_not_found",
+                "shortDescription": { "text": "Declaration Error" },
+            }])
+        );
+        assert_eq!(run["results"][0]["level"], json!("error"));
+        assert_eq!(
+            run["results"][0]["message"]["text"],
+            json!("Declaration Error: module 'hat' not found")
+        );
+        assert_eq!(
+            run["results"][0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            json!("avatars/base.declisp")
+        );
+    }
+
+    #[test]
+    fn reports_load_failure_in_json() {
+        let outcome = CompileOutcome::LoadFailed(anyhow!("schema directory not found"));
+        let document = construct_json(&outcome, &i18n_log()).expect("should construct");
+
+        assert_eq!(
+            document,
+            json!({
+                "success": false,
+                "avatar": null,
+                "error": "schema directory not found",
+                "diagnostics": [],
+            })
+        );
+    }
+}
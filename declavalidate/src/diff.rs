@@ -0,0 +1,160 @@
+use anyhow::Result;
+use declavatar::avatar_v2::{
+    analysis::diff::{AvatarDiff, LayerChange, MenuChange, OptionChange, ParameterChange, TargetChange},
+    data::parameter::{DeclaredParameter, ParameterScope, ParameterType},
+};
+
+// synced parameter memory available for an avatar
+pub const SYNCED_BITS_LIMIT: usize = 256;
+
+pub fn print_avatar_diff(diff: &AvatarDiff, json: bool, indented: bool) -> Result<()> {
+    if json {
+        let json = if indented {
+            serde_json::to_string_pretty(diff)
+        } else {
+            serde_json::to_string(diff)
+        }?;
+        println!("{json}");
+        return Ok(());
+    }
+
+    if diff.is_empty() {
+        println!("no changes");
+        return Ok(());
+    }
+
+    if !diff.parameters.is_empty() {
+        println!("parameters:");
+        for change in &diff.parameters {
+            match change {
+                ParameterChange::Added(p) => println!("  + {}", parameter_label(p)),
+                ParameterChange::Removed(p) => println!("  - {}", parameter_label(p)),
+                ParameterChange::Modified {
+                    name,
+                    value_type,
+                    scope,
+                } => {
+                    println!("  ~ {name}");
+                    if let Some((old, new)) = value_type {
+                        println!("      type: {} -> {}", type_label(*old), type_label(*new));
+                    }
+                    if let Some((old, new)) = scope {
+                        println!("      scope: {} -> {}", scope_label(*old), scope_label(*new));
+                    }
+                }
+            }
+        }
+    }
+
+    if !diff.menu_items.is_empty() {
+        println!("menu:");
+        for change in &diff.menu_items {
+            match change {
+                MenuChange::Added(path) => println!("  + {path}"),
+                MenuChange::Removed(path) => println!("  - {path}"),
+                MenuChange::Modified(path) => println!("  ~ {path}"),
+            }
+        }
+    }
+
+    if !diff.layers.is_empty() {
+        println!("layers:");
+        for change in &diff.layers {
+            match change {
+                LayerChange::Added(name) => println!("  + {name}"),
+                LayerChange::Removed(name) => println!("  - {name}"),
+                LayerChange::Modified {
+                    name,
+                    kind,
+                    parameter,
+                    options,
+                    transitions_changed,
+                } => {
+                    println!("  ~ {name}");
+                    if let Some((old, new)) = kind {
+                        println!("      kind: {old} -> {new}");
+                    }
+                    if let Some((old, new)) = parameter {
+                        println!("      parameter: {old} -> {new}");
+                    }
+                    for option in options {
+                        print_option_change(option);
+                    }
+                    if *transitions_changed {
+                        println!("      transitions changed");
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some((old, new)) = &diff.layer_order {
+        println!("layer order:");
+        println!("  {} -> {}", old.join(", "), new.join(", "));
+    }
+
+    let bits = diff.synced_bits;
+    println!(
+        "synced bits: {} -> {} ({:+}) / {SYNCED_BITS_LIMIT}",
+        bits.old,
+        bits.new,
+        bits.delta()
+    );
+    if bits.new > SYNCED_BITS_LIMIT {
+        println!("  exceeds the limit by {}", bits.new - SYNCED_BITS_LIMIT);
+    }
+    Ok(())
+}
+
+fn print_option_change(change: &OptionChange) {
+    match change {
+        OptionChange::Added(name) => println!("      + {name}"),
+        OptionChange::Removed(name) => println!("      - {name}"),
+        OptionChange::Modified {
+            name,
+            index,
+            settings_changed,
+            targets,
+        } => {
+            println!("      ~ {name}");
+            if let Some((old, new)) = index {
+                println!("          index: {old} -> {new}");
+            }
+            if *settings_changed {
+                println!("          state settings changed");
+            }
+            for target in targets {
+                match target {
+                    TargetChange::Added(key) => println!("          + {key}"),
+                    TargetChange::Removed(key) => println!("          - {key}"),
+                    TargetChange::Modified(key) => println!("          ~ {key}"),
+                }
+            }
+        }
+    }
+}
+
+fn parameter_label(parameter: &DeclaredParameter) -> String {
+    format!(
+        "{} ({}, {})",
+        parameter.name,
+        type_label(parameter.value_type),
+        scope_label(parameter.scope)
+    )
+}
+
+fn type_label(value_type: ParameterType) -> String {
+    match value_type {
+        ParameterType::Int(v) => format!("int = {v}"),
+        ParameterType::Float(v) => format!("float = {v}"),
+        ParameterType::Bool(v) => format!("bool = {v}"),
+    }
+}
+
+fn scope_label(scope: ParameterScope) -> String {
+    match scope {
+        ParameterScope::Internal => "internal".to_string(),
+        ParameterScope::Local(true) | ParameterScope::Synced(true) => format!("{}, saved", scope.name()),
+        ParameterScope::Local(false) | ParameterScope::Synced(false) => scope.name().to_string(),
+    }
+}
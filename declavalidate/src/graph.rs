@@ -0,0 +1,333 @@
+use crate::application::GraphFormat;
+
+use std::fmt::Write;
+
+use declavatar::avatar_v2::data::layer::{
+    Layer, LayerContent, LayerGroupOption, LayerRawAnimationKind, LayerRawCondition, LayerRawState,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NodeKind {
+    Parameter,
+    State,
+    DefaultState,
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    id: String,
+    label: String,
+    kind: NodeKind,
+}
+
+#[derive(Debug, Clone)]
+struct Edge {
+    from: String,
+    to: String,
+    label: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct Cluster {
+    id: String,
+    label: String,
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+}
+
+/// Renders layers as a graph, each layer in its own cluster.
+pub fn render_layers<'a>(layers: impl IntoIterator<Item = &'a Layer>, format: GraphFormat) -> String {
+    let clusters: Vec<_> = layers
+        .into_iter()
+        .enumerate()
+        .map(|(i, layer)| construct_cluster(&format!("l{i}"), layer))
+        .collect();
+    match format {
+        GraphFormat::Dot => render_dot(&clusters),
+        GraphFormat::Mermaid => render_mermaid(&clusters),
+    }
+}
+
+fn construct_cluster(id: &str, layer: &Layer) -> Cluster {
+    let mut cluster = Cluster {
+        id: id.to_string(),
+        label: layer.name.clone(),
+        nodes: vec![],
+        edges: vec![],
+    };
+
+    match &layer.content {
+        LayerContent::Group {
+            parameter,
+            default,
+            options,
+        } => {
+            let parameter_id = cluster.add_node("p", parameter.clone(), NodeKind::Parameter);
+            let default_id = cluster.add_node("o_default", option_label(default), NodeKind::DefaultState);
+            cluster.add_edge(&parameter_id, &default_id, Some(format!("== {}", default.value)));
+            for (i, option) in options.iter().enumerate() {
+                let option_id = cluster.add_node(&format!("o{i}"), option_label(option), NodeKind::State);
+                cluster.add_edge(&parameter_id, &option_id, Some(format!("== {}", option.value)));
+            }
+        }
+        LayerContent::Switch { parameter, .. } => {
+            let parameter_id = cluster.add_node("p", parameter.clone(), NodeKind::Parameter);
+            cluster.add_switch_states(&parameter_id);
+        }
+        LayerContent::SwitchGate { gate, .. } => {
+            let gate_id = cluster.add_node("p", format!("gate: {gate}"), NodeKind::Parameter);
+            cluster.add_switch_states(&gate_id);
+        }
+        LayerContent::Puppet { parameter, .. } => {
+            let parameter_id = cluster.add_node("p", parameter.clone(), NodeKind::Parameter);
+            let animation_id = cluster.add_node("puppet", "puppet".to_string(), NodeKind::DefaultState);
+            cluster.add_edge(&parameter_id, &animation_id, Some("time".to_string()));
+        }
+        LayerContent::Raw {
+            default_index,
+            states,
+            transitions,
+        } => {
+            for (i, state) in states.iter().enumerate() {
+                let kind = if i == *default_index {
+                    NodeKind::DefaultState
+                } else {
+                    NodeKind::State
+                };
+                cluster.add_node(&format!("s{i}"), raw_state_label(state), kind);
+            }
+            for transition in transitions {
+                let conditions: Vec<_> = transition.conditions.iter().map(condition_label).collect();
+                let mut label = conditions.join(" && ");
+                if transition.duration > 0.0 {
+                    write!(label, " ({}s)", transition.duration).expect("should write");
+                }
+                cluster.add_edge(
+                    &format!("{id}_s{}", transition.from_index),
+                    &format!("{id}_s{}", transition.target_index),
+                    Some(label.trim().to_string()).filter(|l| !l.is_empty()),
+                );
+            }
+        }
+    }
+
+    cluster
+}
+
+impl Cluster {
+    fn add_node(&mut self, suffix: &str, label: String, kind: NodeKind) -> String {
+        let id = format!("{}_{suffix}", self.id);
+        self.nodes.push(Node {
+            id: id.clone(),
+            label,
+            kind,
+        });
+        id
+    }
+
+    fn add_edge(&mut self, from: &str, to: &str, label: Option<String>) {
+        self.edges.push(Edge {
+            from: from.to_string(),
+            to: to.to_string(),
+            label,
+        });
+    }
+
+    fn add_switch_states(&mut self, parameter_id: &str) {
+        let disabled_id = self.add_node("disabled", "disabled".to_string(), NodeKind::DefaultState);
+        let enabled_id = self.add_node("enabled", "enabled".to_string(), NodeKind::State);
+        self.add_edge(parameter_id, &disabled_id, Some("false".to_string()));
+        self.add_edge(parameter_id, &enabled_id, Some("true".to_string()));
+    }
+}
+
+fn option_label(option: &LayerGroupOption) -> String {
+    format!("{} ({})", option.name, option.value)
+}
+
+fn raw_state_label(state: &LayerRawState) -> String {
+    match &state.animation {
+        LayerRawAnimationKind::Clip { speed_by, time_by, .. } => {
+            let mut label = state.name.clone();
+            if let Some(speed_by) = speed_by {
+                write!(label, "\nspeed: {speed_by}").expect("should write");
+            }
+            if let Some(time_by) = time_by {
+                write!(label, "\ntime: {time_by}").expect("should write");
+            }
+            label
+        }
+        LayerRawAnimationKind::BlendTree { blend_type, params, .. } => {
+            format!("{}\n{blend_type:?} ({})", state.name, params.join(", "))
+        }
+    }
+}
+
+fn condition_label(condition: &LayerRawCondition) -> String {
+    match condition {
+        LayerRawCondition::Be(p) => p.clone(),
+        LayerRawCondition::Not(p) => format!("!{p}"),
+        LayerRawCondition::EqInt(p, v) => format!("{p} == {v}"),
+        LayerRawCondition::NeqInt(p, v) => format!("{p} != {v}"),
+        LayerRawCondition::GtInt(p, v) => format!("{p} > {v}"),
+        LayerRawCondition::LeInt(p, v) => format!("{p} < {v}"),
+        LayerRawCondition::GtFloat(p, v) => format!("{p} > {v}"),
+        LayerRawCondition::LeFloat(p, v) => format!("{p} < {v}"),
+    }
+}
+
+fn render_dot(clusters: &[Cluster]) -> String {
+    let mut dot = String::from("digraph fx_controller {\n    rankdir=LR;\n    node [fontname=\"sans-serif\"];\n");
+    for cluster in clusters {
+        writeln!(dot, "    subgraph cluster_{} {{", cluster.id).expect("should write");
+        writeln!(dot, "        label=\"{}\";", escape_dot(&cluster.label)).expect("should write");
+        for node in &cluster.nodes {
+            let attributes = match node.kind {
+                NodeKind::Parameter => "shape=ellipse, style=filled, fillcolor=lightgray",
+                NodeKind::State => "shape=box",
+                NodeKind::DefaultState => "shape=box, peripheries=2",
+            };
+            writeln!(
+                dot,
+                "        {} [label=\"{}\", {attributes}];",
+                node.id,
+                escape_dot(&node.label)
+            )
+            .expect("should write");
+        }
+        for edge in &cluster.edges {
+            match &edge.label {
+                Some(label) => writeln!(
+                    dot,
+                    "        {} -> {} [label=\"{}\"];",
+                    edge.from,
+                    edge.to,
+                    escape_dot(label)
+                ),
+                None => writeln!(dot, "        {} -> {};", edge.from, edge.to),
+            }
+            .expect("should write");
+        }
+        dot.push_str("    }\n");
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+fn render_mermaid(clusters: &[Cluster]) -> String {
+    let mut mermaid = String::from("flowchart LR\n");
+    for cluster in clusters {
+        writeln!(
+            mermaid,
+            "    subgraph {} [\"{}\"]",
+            cluster.id,
+            escape_mermaid(&cluster.label)
+        )
+        .expect("should write");
+        for node in &cluster.nodes {
+            let label = escape_mermaid(&node.label);
+            match node.kind {
+                NodeKind::Parameter => writeln!(mermaid, "        {}([\"{label}\"])", node.id),
+                NodeKind::State => writeln!(mermaid, "        {}[\"{label}\"]", node.id),
+                NodeKind::DefaultState => writeln!(mermaid, "        {}[[\"{label}\"]]", node.id),
+            }
+            .expect("should write");
+        }
+        for edge in &cluster.edges {
+            match &edge.label {
+                Some(label) => writeln!(
+                    mermaid,
+                    "        {} -->|\"{}\"| {}",
+                    edge.from,
+                    escape_mermaid(label),
+                    edge.to
+                ),
+                None => writeln!(mermaid, "        {} --> {}", edge.from, edge.to),
+            }
+            .expect("should write");
+        }
+        mermaid.push_str("    end\n");
+    }
+    mermaid
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn escape_mermaid(text: &str) -> String {
+    text.replace('"', "#quot;").replace('\n', "<br>")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{application::GraphFormat, graph::render_layers};
+
+    use declavatar::avatar_v2::data::layer::{
+        Layer, LayerAnimation, LayerContent, LayerRawAnimationKind, LayerRawCondition, LayerRawState,
+        LayerRawTransition,
+    };
+    use pretty_assertions::assert_eq;
+
+    fn raw_state(name: &str, speed_by: Option<&str>) -> LayerRawState {
+        LayerRawState {
+            name: name.to_string(),
+            animation: LayerRawAnimationKind::Clip {
+                animation: LayerAnimation::Inline(vec![]),
+                speed: None,
+                speed_by: speed_by.map(|s| s.to_string()),
+                time_by: None,
+            },
+        }
+    }
+
+    fn quoted_layer() -> Layer {
+        Layer {
+            name: r#"Say "Hi""#.to_string(),
+            content: LayerContent::Raw {
+                default_index: 0,
+                states: vec![raw_state(r#""Idle""#, Some("Speed")), raw_state(r"Wave\Hand", None)],
+                transitions: vec![LayerRawTransition {
+                    from_index: 0,
+                    target_index: 1,
+                    duration: 0.0,
+                    conditions: vec![LayerRawCondition::Be("Go".to_string())],
+                }],
+            },
+        }
+    }
+
+    #[test]
+    fn escapes_labels_in_dot() {
+        let layers = [quoted_layer()];
+        assert_eq!(
+            render_layers(&layers, GraphFormat::Dot),
+            r#"digraph fx_controller {
+    rankdir=LR;
+    node [fontname="sans-serif"];
+    subgraph cluster_l0 {
+        label="Say \"Hi\"";
+        l0_s0 [label="\"Idle\"\nspeed: Speed", shape=box, peripheries=2];
+        l0_s1 [label="Wave\\Hand", shape=box];
+        l0_s0 -> l0_s1 [label="Go"];
+    }
+}
+"#
+        );
+    }
+
+    #[test]
+    fn escapes_labels_in_mermaid() {
+        let layers = [quoted_layer()];
+        assert_eq!(
+            render_layers(&layers, GraphFormat::Mermaid),
+            r##"flowchart LR
+    subgraph l0 ["Say #quot;Hi#quot;"]
+        l0_s0[["#quot;Idle#quot;<br>speed: Speed"]]
+        l0_s1["Wave\Hand"]
+        l0_s0 -->|"Go"| l0_s1
+    end
+"##
+        );
+    }
+}
@@ -1,26 +1,54 @@
 mod application;
+mod diagnostics;
+mod diff;
+mod graph;
+mod variants;
+mod watch;
+mod xref;
 
-use crate::application::{Arguments, FileOption, Subcommand};
+use crate::{
+    application::{validate_variant_name, Arguments, DiagnosticsFormat, FileOption, Subcommand, VariantOption},
+    diagnostics::{print_diagnostics, CompileOutcome, EXIT_LOAD_FAILURE},
+    diff::print_avatar_diff,
+    graph::render_layers,
+    variants::print_variant_report,
+    watch::{watch, FileWatcher, WatchedOutput},
+    xref::print_cross_reference,
+};
 
-use std::{collections::HashMap, fs::read_to_string, path::PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{create_dir_all, read_to_string, write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
 
-use anyhow::{bail, Result};
+use anyhow::{bail, Error, Result};
 use clap::Parser;
 use declavatar::{
-    avatar_v2::Transformer,
-    decl_v2::{compile_declaration, data::avatar::DeclAvatar, Arguments as DeclArguments, DeclarationFormat},
+    avatar_v2::{
+        analysis::{diff::AvatarDiff, variants::VariantReport, xref::CrossReference},
+        data::avatar::{Avatar, AVATAR_FORMAT_VERSION},
+        TransformResult, Transformer,
+    },
+    decl_v2::{
+        compile_declarations, data::avatar::DeclAvatar, Arguments as DeclArguments, DeclarationFormat,
+        DeclarationSource,
+    },
     i18n::get_log_messages,
+    project::{Project, MANIFEST_FILENAME},
 };
 use strfmt::Format;
 use sys_locale::get_locale;
 
-fn main() -> Result<()> {
+fn main() -> Result<ExitCode> {
     let args = Arguments::parse();
 
     match args.subcommand {
         Subcommand::Load(file_option) => {
-            let decl_args = construct_decl_arguments(&file_option);
-            let decl_avatar = load_declaration_auto(file_option.file, decl_args);
+            let decl_avatar = find_project(&file_option)
+                .and_then(|project| construct_decl_arguments(&file_option, project.as_ref()))
+                .and_then(|decl_args| load_declaration_auto(&file_option, decl_args));
             match decl_avatar {
                 Ok(a) => {
                     if file_option.indented {
@@ -31,49 +59,292 @@ fn main() -> Result<()> {
                 }
                 Err(e) => {
                     eprintln!("Error: {e}");
+                    return Ok(ExitCode::from(EXIT_LOAD_FAILURE));
                 }
             }
         }
         Subcommand::Compile {
             file_option,
             arbittach_schema_files,
+            watch: false,
+            diagnostics_format,
         } => {
-            let mut transformer = Transformer::new();
-            for schema_path in arbittach_schema_files {
-                let schema_json = read_to_string(schema_path)?;
-                let schema = serde_json::from_str(&schema_json)?;
-                transformer.register_arbittach_schema(schema);
-            }
+            let outcome = CompileOutcome::new(compile_avatar(&file_option, &arbittach_schema_files));
+            print_diagnostics(&outcome, diagnostics_format, &file_option.file, file_option.indented)?;
+            return Ok(outcome.exit_code());
+        }
+        Subcommand::Compile {
+            file_option,
+            arbittach_schema_files,
+            watch: true,
+            ..
+        } => {
+            let project_paths = match find_project(&file_option) {
+                Ok(project) => project.map(|project| project.dependent_paths()).unwrap_or_default(),
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    return Ok(ExitCode::from(EXIT_LOAD_FAILURE));
+                }
+            };
+            let targets = file_option
+                .modules
+                .iter()
+                .chain(&file_option.library_paths)
+                .chain(&arbittach_schema_files)
+                .cloned()
+                .chain(project_paths)
+                .chain([file_option.file.clone()]);
+            let watcher = FileWatcher::new(targets);
+            let i18n_log = I18nLog::load_current_locale();
+            watch(watcher, file_option.indented, || {
+                let avatar_result = compile_avatar(&file_option, &arbittach_schema_files)?;
+                let diagnostics = avatar_result
+                    .logs
+                    .into_iter()
+                    .map(|log| {
+                        let message = i18n_log.localize(log.kind, log.args);
+                        let contexts: String = log.context.iter().map(|ctx| format!(" @ {ctx}")).collect();
+                        format!("{:?}: {message}{contexts}", log.severity)
+                    })
+                    .collect();
+                let avatar = avatar_result.avatar.map(serde_json::to_value).transpose()?;
+                Ok(WatchedOutput { diagnostics, avatar })
+            });
+        }
+        Subcommand::Graph {
+            file_option,
+            arbittach_schema_files,
+            format,
+            layers,
+        } => {
+            let outcome = CompileOutcome::new(compile_avatar(&file_option, &arbittach_schema_files));
+            let Some(avatar) = outcome.avatar() else {
+                print_diagnostics(
+                    &outcome,
+                    DiagnosticsFormat::Text,
+                    &file_option.file,
+                    file_option.indented,
+                )?;
+                return Ok(outcome.exit_code());
+            };
 
-            let decl_args = construct_decl_arguments(&file_option);
-            let decl_avatar = load_declaration_auto(file_option.file, decl_args)?;
-
-            let avatar_result = transformer.transform_avatar(decl_avatar);
-            if let Some(avatar) = avatar_result.avatar {
-                let json = if file_option.indented {
-                    serde_json::to_string_pretty(&avatar)
-                } else {
-                    serde_json::to_string(&avatar)
-                }?;
-                println!("{json}");
-            } else {
-                let i18n_log = I18nLog::load_current_locale();
-                for log in avatar_result.logs {
-                    let message = i18n_log.localize(log.kind, log.args);
-                    println!("{:?}: {message}", log.severity);
-                    for ctx in log.context {
-                        println!("@ {ctx}");
+            let rendered_layers = avatar
+                .fx_controller
+                .iter()
+                .filter(|layer| layers.is_empty() || layers.contains(&layer.name));
+            print!("{}", render_layers(rendered_layers, format));
+        }
+        Subcommand::Xref {
+            file_option,
+            arbittach_schema_files,
+            parameters,
+            json,
+        } => {
+            let outcome = CompileOutcome::new(compile_avatar(&file_option, &arbittach_schema_files));
+            let Some(avatar) = outcome.avatar() else {
+                print_diagnostics(
+                    &outcome,
+                    DiagnosticsFormat::Text,
+                    &file_option.file,
+                    file_option.indented,
+                )?;
+                return Ok(outcome.exit_code());
+            };
+
+            let xref = CrossReference::analyze(avatar);
+            print_cross_reference(&xref, &parameters, json, file_option.indented)?;
+        }
+        Subcommand::Diff {
+            file_option,
+            new_file,
+            arbittach_schema_files,
+            json,
+        } => {
+            let new_file_option = FileOption {
+                file: new_file,
+                ..file_option.clone()
+            };
+            let mut avatars = vec![];
+            for file_option in [&file_option, &new_file_option] {
+                if file_option.file.extension().is_some_and(|ext| ext == "json") {
+                    match read_avatar_json(&file_option.file) {
+                        Ok(avatar) => avatars.push(avatar),
+                        Err(e) => {
+                            eprintln!("Error: {e}");
+                            return Ok(ExitCode::from(EXIT_LOAD_FAILURE));
+                        }
                     }
-                    println!();
+                    continue;
                 }
+
+                let outcome = CompileOutcome::new(compile_avatar(file_option, &arbittach_schema_files));
+                let Some(avatar) = outcome.avatar() else {
+                    eprintln!("{} failed to compile:", file_option.file.display());
+                    print_diagnostics(
+                        &outcome,
+                        DiagnosticsFormat::Text,
+                        &file_option.file,
+                        file_option.indented,
+                    )?;
+                    return Ok(outcome.exit_code());
+                };
+                avatars.push(avatar.clone());
             }
+
+            let diff = AvatarDiff::compare(&avatars[0], &avatars[1]);
+            print_avatar_diff(&diff, json, file_option.indented)?;
+        }
+        Subcommand::Variants {
+            file_option,
+            arbittach_schema_files,
+            variants,
+            output_directory,
+            json,
+        } => {
+            let variants = match resolve_variants(&file_option, variants) {
+                Ok(variants) => variants,
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    return Ok(ExitCode::from(EXIT_LOAD_FAILURE));
+                }
+            };
+
+            let mut avatars = vec![];
+            for variant in &variants {
+                let variant_option = match &variant.symbols {
+                    Some(symbols) => FileOption {
+                        symbols: file_option.symbols.iter().chain(symbols).cloned().collect(),
+                        ..file_option.clone()
+                    },
+                    None => FileOption {
+                        profile: Some(variant.name.clone()),
+                        ..file_option.clone()
+                    },
+                };
+                let outcome = CompileOutcome::new(compile_avatar(&variant_option, &arbittach_schema_files));
+                let Some(avatar) = outcome.avatar() else {
+                    eprintln!("variant {} failed to compile:", variant.name);
+                    print_diagnostics(
+                        &outcome,
+                        DiagnosticsFormat::Text,
+                        &file_option.file,
+                        file_option.indented,
+                    )?;
+                    return Ok(outcome.exit_code());
+                };
+                avatars.push((variant.name.as_str(), avatar.clone()));
+            }
+
+            if let Some(output_directory) = &output_directory {
+                create_dir_all(output_directory)?;
+                for (name, avatar) in &avatars {
+                    let json = if file_option.indented {
+                        serde_json::to_string_pretty(avatar)
+                    } else {
+                        serde_json::to_string(avatar)
+                    }?;
+                    write(output_directory.join(format!("{name}.json")), json)?;
+                }
+            }
+
+            let report = VariantReport::analyze(avatars.iter().map(|(name, avatar)| (*name, avatar)));
+            print_variant_report(&report, json, file_option.indented)?;
+        }
+        Subcommand::Schema { indented } => {
+            let schema = Avatar::json_schema();
+            let json = if indented {
+                serde_json::to_string_pretty(&schema)
+            } else {
+                serde_json::to_string(&schema)
+            }?;
+            println!("{json}");
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn compile_avatar(file_option: &FileOption, arbittach_schema_files: &[PathBuf]) -> Result<TransformResult> {
+    let project = find_project(file_option)?;
+
+    let mut transformer = Transformer::new();
+    if let Some(project) = &project {
+        project.register_attachment_schemas(&mut transformer)?;
+    }
+    for schema_path in arbittach_schema_files {
+        if schema_path.is_dir() {
+            transformer.load_arbittach_schemas(schema_path)?;
+        } else {
+            transformer.load_arbittach_schema_file(schema_path)?;
+        }
+    }
+
+    let decl_args = construct_decl_arguments(file_option, project.as_ref())?;
+    let decl_avatar = load_declaration_auto(file_option, decl_args)?;
+    Ok(transformer.transform_avatar(decl_avatar))
+}
+
+// profiles in the manifest are used if no variant is given
+fn resolve_variants(file_option: &FileOption, variants: Vec<VariantOption>) -> Result<Vec<VariantOption>> {
+    let variants = if variants.is_empty() {
+        let Some(project) = find_project(file_option)? else {
+            bail!("no variant is specified, and {MANIFEST_FILENAME} is not found");
+        };
+        project
+            .manifest()
+            .profiles
+            .keys()
+            .map(|name| VariantOption {
+                name: name.clone(),
+                symbols: None,
+            })
+            .collect()
+    } else {
+        variants
+    };
+    if variants.is_empty() {
+        bail!("no profile is defined in {MANIFEST_FILENAME}");
+    }
+
+    let mut names = HashSet::new();
+    for variant in &variants {
+        validate_variant_name(&variant.name).map_err(Error::msg)?;
+        if !names.insert(variant.name.as_str()) {
+            bail!("variant {} is specified more than once", variant.name);
         }
     }
-    Ok(())
+    Ok(variants)
 }
 
-fn construct_decl_arguments(file_option: &FileOption) -> DeclArguments {
+fn read_avatar_json(file: &Path) -> Result<Avatar> {
+    let avatar: Avatar = serde_json::from_str(&read_to_string(file)?)?;
+    if avatar.format_version != AVATAR_FORMAT_VERSION {
+        eprintln!(
+            "Warning: {} has format version {}, but current version is {AVATAR_FORMAT_VERSION}",
+            file.display(),
+            avatar.format_version
+        );
+    }
+    Ok(avatar)
+}
+
+fn find_project(file_option: &FileOption) -> Result<Option<Project>> {
+    let project = if file_option.no_project {
+        None
+    } else {
+        Project::discover(&file_option.file)?
+    };
+    if project.is_none() && file_option.profile.is_some() {
+        bail!("profile is specified, but {MANIFEST_FILENAME} is not found");
+    }
+    Ok(project)
+}
+
+// options given in command line are applied after the project
+fn construct_decl_arguments(file_option: &FileOption, project: Option<&Project>) -> Result<DeclArguments> {
     let mut args = DeclArguments::new();
+    if let Some(project) = project {
+        project.configure_arguments(&mut args, file_option.profile.as_deref())?;
+    }
     for library_path in &file_option.library_paths {
         args.add_library_path(library_path);
     }
@@ -84,10 +355,38 @@ fn construct_decl_arguments(file_option: &FileOption) -> DeclArguments {
         args.define_localization(key, value);
     }
 
-    args
+    Ok(args)
 }
 
-fn load_declaration_auto(file: PathBuf, args: DeclArguments) -> Result<DeclAvatar> {
+fn load_declaration_auto(file_option: &FileOption, args: DeclArguments) -> Result<DeclAvatar> {
+    let base_name = file_option.file.to_string_lossy();
+    let base_text = read_to_string(&file_option.file)?;
+    let base = DeclarationSource {
+        name: &base_name,
+        text: &base_text,
+        format: determine_format(&file_option.file)?,
+    };
+
+    let mut module_files = vec![];
+    for module_path in &file_option.modules {
+        let name = module_path.to_string_lossy().to_string();
+        let text = read_to_string(module_path)?;
+        module_files.push((name, text, determine_format(module_path)?));
+    }
+    let modules: Vec<_> = module_files
+        .iter()
+        .map(|(name, text, format)| DeclarationSource {
+            name,
+            text,
+            format: *format,
+        })
+        .collect();
+
+    let decl_avatar = compile_declarations(base, &modules, args)?;
+    Ok(decl_avatar)
+}
+
+fn determine_format(file: &Path) -> Result<DeclarationFormat> {
     let file_ext = file.extension();
     let Some(file_ext) = file_ext else {
         bail!("file format cannot be determined");
@@ -97,10 +396,7 @@ fn load_declaration_auto(file: PathBuf, args: DeclArguments) -> Result<DeclAvata
         "declua" | "lua" => DeclarationFormat::Lua,
         ext => bail!("unknown file type: {ext}"),
     };
-
-    let text = read_to_string(file)?;
-    let decl_avatar = compile_declaration(&text, format, args)?;
-    Ok(decl_avatar)
+    Ok(format)
 }
 
 struct I18nLog {
@@ -118,6 +414,10 @@ impl I18nLog {
         I18nLog { localization }
     }
 
+    fn title<'a>(&'a self, kind: &'a str) -> &'a str {
+        self.localization.get(kind).map(|t| t.as_str()).unwrap_or(kind)
+    }
+
     fn localize(&self, kind: String, args: Vec<String>) -> String {
         let title = self.localization.get(&kind).unwrap_or(&kind);
         let Some(description) = self.localization.get(&format!("{kind}:description")) else {
@@ -0,0 +1,58 @@
+use crate::diff::SYNCED_BITS_LIMIT;
+
+use anyhow::Result;
+use declavatar::avatar_v2::analysis::variants::{VariantDifference, VariantReport};
+
+pub fn print_variant_report(report: &VariantReport, json: bool, indented: bool) -> Result<()> {
+    if json {
+        let json = if indented {
+            serde_json::to_string_pretty(report)
+        } else {
+            serde_json::to_string(report)
+        }?;
+        println!("{json}");
+        return Ok(());
+    }
+
+    println!("variants: {}", report.variants.join(", "));
+    if report.is_empty() {
+        println!("no differences");
+        return Ok(());
+    }
+
+    for (title, differences) in [
+        ("parameters", &report.parameters),
+        ("layers", &report.layers),
+        ("menu", &report.menu_items),
+    ] {
+        if differences.is_empty() {
+            continue;
+        }
+        println!("{title}:");
+        for difference in differences {
+            print_difference(difference, report.variants.len());
+        }
+    }
+
+    println!("synced bits:");
+    for (variant, bits) in report.variants.iter().zip(&report.synced_bits) {
+        println!("  {variant}: {bits} / {SYNCED_BITS_LIMIT}");
+        if *bits > SYNCED_BITS_LIMIT {
+            println!("    exceeds the limit by {}", bits - SYNCED_BITS_LIMIT);
+        }
+    }
+    Ok(())
+}
+
+fn print_difference(difference: &VariantDifference, variants_count: usize) {
+    let presence = if difference.present_in.len() == variants_count {
+        "all variants".to_string()
+    } else {
+        format!("only in {}", difference.present_in.join(", "))
+    };
+    if difference.modified {
+        println!("  ~ {} ({presence}, modified)", difference.name);
+    } else {
+        println!("  {} ({presence})", difference.name);
+    }
+}
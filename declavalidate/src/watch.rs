@@ -0,0 +1,249 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{Display, Formatter, Result as FmtResult},
+    fs::{metadata, read_dir},
+    path::{Path, PathBuf},
+    thread::sleep,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::Result;
+use serde_json::Value;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Output of a single compilation, compared between runs.
+#[derive(Debug, Clone, Default)]
+pub struct WatchedOutput {
+    pub diagnostics: Vec<String>,
+    pub avatar: Option<Value>,
+}
+
+/// Polls modification times of files, including those under directories.
+#[derive(Debug)]
+pub struct FileWatcher {
+    targets: Vec<PathBuf>,
+    snapshot: BTreeMap<PathBuf, SystemTime>,
+}
+
+impl FileWatcher {
+    pub fn new(targets: impl IntoIterator<Item = PathBuf>) -> FileWatcher {
+        let targets: Vec<_> = targets.into_iter().collect();
+        let snapshot = take_snapshot(&targets);
+        FileWatcher { targets, snapshot }
+    }
+
+    /// Blocks until any target is added, removed or modified.
+    pub fn wait_for_change(&mut self) {
+        loop {
+            sleep(POLL_INTERVAL);
+            let snapshot = take_snapshot(&self.targets);
+            if snapshot != self.snapshot {
+                self.snapshot = snapshot;
+                return;
+            }
+        }
+    }
+}
+
+fn take_snapshot(targets: &[PathBuf]) -> BTreeMap<PathBuf, SystemTime> {
+    let mut snapshot = BTreeMap::new();
+    for target in targets {
+        collect_mtimes(target, &mut snapshot);
+    }
+    snapshot
+}
+
+fn collect_mtimes(path: &Path, snapshot: &mut BTreeMap<PathBuf, SystemTime>) {
+    // files may disappear while saving, they will be picked up in next poll
+    let Ok(meta) = metadata(path) else {
+        return;
+    };
+    if meta.is_dir() {
+        let Ok(entries) = read_dir(path) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            collect_mtimes(&entry.path(), snapshot);
+        }
+    } else if let Ok(modified) = meta.modified() {
+        snapshot.insert(path.to_path_buf(), modified);
+    }
+}
+
+/// Runs `compile` every time watched files change, printing differences from previous run.
+pub fn watch(mut watcher: FileWatcher, indented: bool, mut compile: impl FnMut() -> Result<WatchedOutput>) -> ! {
+    let mut previous: Option<WatchedOutput> = None;
+    loop {
+        match compile() {
+            Ok(output) => {
+                match &previous {
+                    Some(previous) => print_output_diff(previous, &output),
+                    None => print_output(&output, indented),
+                }
+                previous = Some(output);
+            }
+            Err(e) => {
+                // keeps previous output so that the next successful run is compared with it
+                eprintln!("Error: {e}");
+            }
+        }
+
+        eprintln!("--- waiting for changes ---");
+        watcher.wait_for_change();
+    }
+}
+
+fn print_output(output: &WatchedOutput, indented: bool) {
+    for diagnostic in &output.diagnostics {
+        println!("{diagnostic}");
+    }
+    if let Some(avatar) = &output.avatar {
+        let json = if indented {
+            serde_json::to_string_pretty(avatar)
+        } else {
+            serde_json::to_string(avatar)
+        }
+        .expect("should be serialized");
+        println!("{json}");
+    }
+}
+
+fn print_output_diff(previous: &WatchedOutput, current: &WatchedOutput) {
+    let previous_diagnostics: BTreeSet<_> = previous.diagnostics.iter().collect();
+    let current_diagnostics: BTreeSet<_> = current.diagnostics.iter().collect();
+    for resolved in previous_diagnostics.difference(&current_diagnostics) {
+        println!("- {resolved}");
+    }
+    for added in current_diagnostics.difference(&previous_diagnostics) {
+        println!("+ {added}");
+    }
+
+    let changes = match (&previous.avatar, &current.avatar) {
+        (Some(previous), Some(current)) => diff_json(previous, current),
+        (None, Some(avatar)) => {
+            println!("avatar compiled: {avatar}");
+            vec![]
+        }
+        (Some(_), None) => {
+            println!("avatar failed to compile");
+            vec![]
+        }
+        (None, None) => vec![],
+    };
+    if changes.is_empty() && previous_diagnostics == current_diagnostics && previous.avatar == current.avatar {
+        println!("no changes");
+    }
+    for change in changes {
+        println!("{change}");
+    }
+}
+
+/// A difference between two JSON values, located by JSON Pointer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonChange {
+    Added(String, Value),
+    Removed(String, Value),
+    Modified(String, Value, Value),
+}
+
+impl Display for JsonChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            JsonChange::Added(pointer, value) => write!(f, "+ {pointer}: {value}"),
+            JsonChange::Removed(pointer, value) => write!(f, "- {pointer}: {value}"),
+            JsonChange::Modified(pointer, old, new) => write!(f, "~ {pointer}: {old} -> {new}"),
+        }
+    }
+}
+
+pub fn diff_json(old: &Value, new: &Value) -> Vec<JsonChange> {
+    let mut changes = vec![];
+    diff_json_at("", old, new, &mut changes);
+    changes
+}
+
+fn diff_json_at(pointer: &str, old: &Value, new: &Value, changes: &mut Vec<JsonChange>) {
+    match (old, new) {
+        (Value::Object(old_map), Value::Object(new_map)) => {
+            for (key, old_value) in old_map {
+                let child = format!("{pointer}/{}", escape_pointer(key));
+                match new_map.get(key) {
+                    Some(new_value) => diff_json_at(&child, old_value, new_value, changes),
+                    None => changes.push(JsonChange::Removed(child, old_value.clone())),
+                }
+            }
+            for (key, new_value) in new_map {
+                if !old_map.contains_key(key) {
+                    let child = format!("{pointer}/{}", escape_pointer(key));
+                    changes.push(JsonChange::Added(child, new_value.clone()));
+                }
+            }
+        }
+        (Value::Array(old_items), Value::Array(new_items)) => {
+            for (i, old_item) in old_items.iter().enumerate() {
+                let child = format!("{pointer}/{i}");
+                match new_items.get(i) {
+                    Some(new_item) => diff_json_at(&child, old_item, new_item, changes),
+                    None => changes.push(JsonChange::Removed(child, old_item.clone())),
+                }
+            }
+            for (i, new_item) in new_items.iter().enumerate().skip(old_items.len()) {
+                changes.push(JsonChange::Added(format!("{pointer}/{i}"), new_item.clone()));
+            }
+        }
+        (old, new) if old != new => changes.push(JsonChange::Modified(pointer.to_string(), old.clone(), new.clone())),
+        _ => (),
+    }
+}
+
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::watch::{diff_json, JsonChange};
+
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn escapes_pointer_tokens() {
+        let old = json!({ "a/b": 1, "c~d": { "e": true } });
+        let new = json!({ "a/b": 2, "c~d": {}, "f~/g": null });
+
+        assert_eq!(
+            diff_json(&old, &new),
+            vec![
+                JsonChange::Modified("/a~1b".to_string(), json!(1), json!(2)),
+                JsonChange::Removed("/c~0d/e".to_string(), json!(true)),
+                JsonChange::Added("/f~0~1g".to_string(), json!(null)),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_array_growth_and_shrink() {
+        let short = json!({ "items": [1, 2] });
+        let long = json!({ "items": [1, 3, 4, 5] });
+
+        assert_eq!(
+            diff_json(&short, &long),
+            vec![
+                JsonChange::Modified("/items/1".to_string(), json!(2), json!(3)),
+                JsonChange::Added("/items/2".to_string(), json!(4)),
+                JsonChange::Added("/items/3".to_string(), json!(5)),
+            ]
+        );
+        assert_eq!(
+            diff_json(&long, &short),
+            vec![
+                JsonChange::Modified("/items/1".to_string(), json!(3), json!(2)),
+                JsonChange::Removed("/items/2".to_string(), json!(4)),
+                JsonChange::Removed("/items/3".to_string(), json!(5)),
+            ]
+        );
+        assert_eq!(diff_json(&long, &long), vec![]);
+    }
+}
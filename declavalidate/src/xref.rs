@@ -0,0 +1,74 @@
+use anyhow::Result;
+use declavatar::avatar_v2::analysis::xref::{CrossReference, DriveReference, LayerReadKind};
+
+pub fn print_cross_reference(xref: &CrossReference, filter: &[String], json: bool, indented: bool) -> Result<()> {
+    let shown = |name: &String| filter.is_empty() || filter.contains(name);
+    if json {
+        let mut filtered = xref.clone();
+        filtered.parameters.retain(|name, _| shown(name));
+        let json = if indented {
+            serde_json::to_string_pretty(&filtered)
+        } else {
+            serde_json::to_string(&filtered)
+        }?;
+        println!("{json}");
+        return Ok(());
+    }
+
+    // dependencies are taken from all parameters even if filtered
+    let dependencies = xref.drive_dependencies();
+    for (name, references) in xref.parameters.iter().filter(|(name, _)| shown(name)) {
+        if references.declared {
+            println!("{name}");
+        } else {
+            println!("{name} (not declared)");
+        }
+
+        for menu_item in &references.menu_writers {
+            println!("  written by menu {menu_item}");
+        }
+        for contact in &references.contact_writers {
+            match &contact.root_object {
+                Some(root) => println!("  written by {:?} contact receiver on {root}", contact.receiver_type),
+                None => println!("  written by {:?} contact receiver", contact.receiver_type),
+            }
+        }
+        for drive in &references.drive_writers {
+            match &drive.counterpart {
+                Some(source) => println!("  copied from {source} at {}", drive_location(drive)),
+                None => println!("  driven at {}", drive_location(drive)),
+            }
+        }
+        for drive in &references.drive_readers {
+            let target = drive.counterpart.as_deref().unwrap_or_default();
+            println!("  copied to {target} at {}", drive_location(drive));
+        }
+        for read in &references.layer_readers {
+            let kind = match read.kind {
+                LayerReadKind::DrivenBy => "driven by",
+                LayerReadKind::Condition => "condition of",
+                LayerReadKind::BlendTree => "blend tree of",
+                LayerReadKind::SpeedBy => "speed of",
+                LayerReadKind::TimeBy => "time of",
+            };
+            match &read.location {
+                Some(location) => println!("  {kind} {} / {location}", read.layer),
+                None => println!("  {kind} {}", read.layer),
+            }
+        }
+        for gate in &references.guarded_gates {
+            println!("  guards gate {gate}");
+        }
+
+        if let Some(dependents) = dependencies.get(name.as_str()) {
+            let dependents: Vec<_> = dependents.iter().copied().collect();
+            println!("  affects {}", dependents.join(", "));
+        }
+        println!();
+    }
+    Ok(())
+}
+
+fn drive_location(drive: &DriveReference) -> String {
+    format!("{} / {}", drive.layer, drive.location)
+}
@@ -1,7 +1,10 @@
 use proc_macro::TokenStream;
-use proc_macro2::TokenStream as TokenStream2;
+use proc_macro2::{TokenStream as TokenStream2, TokenTree};
 use quote::{format_ident, quote};
-use syn::{parse_macro_input, Attribute, Data, DeriveInput, Error as SynError, Fields, FieldsUnnamed, LitStr};
+use syn::{
+    parse::ParseStream, parse2, parse_macro_input, Attribute, Data, DeriveInput, Error as SynError, Field, Fields,
+    FieldsUnnamed, Ident, LitInt, LitStr, Token,
+};
 
 #[proc_macro_derive(EnumLog, attributes(log_error, log_warn, log_info))]
 pub fn enum_log_derive(input: TokenStream) -> TokenStream {
@@ -123,3 +126,224 @@ fn enum_log_find_attribute(attrs: &[Attribute]) -> Result<Option<(LitStr, TokenS
     }
     Ok(None)
 }
+
+#[proc_macro_derive(ArbittachSchema, attributes(arbittach))]
+pub fn arbittach_schema_derive(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match arbittach_schema_generate(&input) {
+        Ok(ts) => ts,
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn arbittach_schema_generate(derive_input: &DeriveInput) -> Result<TokenStream, SynError> {
+    let fields = arbittach_struct_fields(derive_input)?;
+    let schema = quote!(::declavatar::avatar_v2::data::attachment::schema);
+    let struct_name = &derive_input.ident;
+    let (impl_generics, ty_generics, where_where) = derive_input.generics.split_for_impl();
+
+    let mut name = LitStr::new(&unraw(struct_name), struct_name.span());
+    let mut version = quote!(0);
+    for (key, value) in arbittach_find_attributes(&derive_input.attrs)? {
+        match key.to_string().as_str() {
+            "name" => name = parse2(arbittach_expect_value(&key, value)?)?,
+            "version" => {
+                let version_literal: LitInt = parse2(arbittach_expect_value(&key, value)?)?;
+                version = quote!(#version_literal);
+            }
+            _ => return Err(SynError::new_spanned(key, "unknown arbittach attribute")),
+        }
+    }
+
+    let mut properties = vec![];
+    for field in fields {
+        let field_ident = field.ident.as_ref().expect("should be named field");
+        let field_type = &field.ty;
+        let mut property_name = LitStr::new(&to_pascal_case(&unraw(field_ident)), field_ident.span());
+        for (key, value) in arbittach_find_attributes(&field.attrs)? {
+            match key.to_string().as_str() {
+                "rename" => property_name = parse2(arbittach_expect_value(&key, value)?)?,
+                _ => return Err(SynError::new_spanned(key, "unknown arbittach attribute")),
+            }
+        }
+
+        properties.push(quote! {
+            #schema::Property {
+                name: #property_name.to_string(),
+                required: <#field_type as #schema::ArbittachProperty>::REQUIRED,
+                parameters: <#field_type as #schema::ArbittachProperty>::parameters(),
+                keywords: <#field_type as #schema::ArbittachProperty>::keywords(),
+            }
+        });
+    }
+
+    let expanded = quote! {
+        impl #impl_generics #schema::ArbittachSchema for #struct_name #ty_generics #where_where {
+            fn arbittach_schema() -> #schema::Attachment {
+                #schema::Attachment {
+                    name: #name.to_string(),
+                    properties: vec![#(#properties),*],
+                    version: #version,
+                }
+            }
+        }
+    };
+    Ok(expanded.into())
+}
+
+#[proc_macro_derive(ArbittachProperty, attributes(arbittach))]
+pub fn arbittach_property_derive(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match arbittach_property_generate(&input) {
+        Ok(ts) => ts,
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn arbittach_property_generate(derive_input: &DeriveInput) -> Result<TokenStream, SynError> {
+    let fields = arbittach_struct_fields(derive_input)?;
+    let schema = quote!(::declavatar::avatar_v2::data::attachment::schema);
+    let struct_name = &derive_input.ident;
+    let (impl_generics, ty_generics, where_where) = derive_input.generics.split_for_impl();
+    if let Some((key, _)) = arbittach_find_attributes(&derive_input.attrs)?.into_iter().next() {
+        return Err(SynError::new_spanned(key, "unknown arbittach attribute"));
+    }
+
+    let mut parameters = vec![];
+    let mut keywords = vec![];
+    for field in fields {
+        let field_ident = field.ident.as_ref().expect("should be named field");
+        let field_type = &field.ty;
+
+        let mut keyword = false;
+        let mut rename = None;
+        let mut value_type = quote!(<#field_type as #schema::ArbittachValue>::value_type());
+        let mut default = None;
+        for (key, value) in arbittach_find_attributes(&field.attrs)? {
+            match key.to_string().as_str() {
+                "keyword" => keyword = true,
+                "rename" => rename = Some(parse2::<LitStr>(arbittach_expect_value(&key, value)?)?),
+                "value_type" => value_type = arbittach_expect_value(&key, value)?,
+                "default" => default = Some(arbittach_expect_value(&key, value)?),
+                _ => return Err(SynError::new_spanned(key, "unknown arbittach attribute")),
+            }
+        }
+        let optional = quote!(<#field_type as #schema::ArbittachValue>::OPTIONAL);
+
+        if keyword {
+            // keywords in lisp style: allow_self -> allow-self
+            let name = rename.unwrap_or_else(|| LitStr::new(&unraw(field_ident).replace('_', "-"), field_ident.span()));
+            let (required, default) = match default {
+                Some(default) => (quote!(false), quote!(Some(#default))),
+                None => (quote!(!#optional), quote!(None)),
+            };
+            keywords.push(quote! {
+                #schema::Keyword {
+                    name: #name.to_string(),
+                    required: #required,
+                    value_type: #value_type,
+                    default: #default,
+                }
+            });
+        } else {
+            let name = rename.unwrap_or_else(|| LitStr::new(&unraw(field_ident), field_ident.span()));
+            let default = match default {
+                Some(default) => quote!(Some(#default)),
+                None => quote!(None),
+            };
+            parameters.push(quote! {
+                #schema::Parameter {
+                    name: #name.to_string(),
+                    value_type: #value_type,
+                    optional: #optional,
+                    default: #default,
+                }
+            });
+        }
+    }
+
+    let expanded = quote! {
+        impl #impl_generics #schema::ArbittachProperty for #struct_name #ty_generics #where_where {
+            fn parameters() -> ::std::vec::Vec<#schema::Parameter> {
+                vec![#(#parameters),*]
+            }
+
+            fn keywords() -> ::std::vec::Vec<#schema::Keyword> {
+                vec![#(#keywords),*]
+            }
+        }
+    };
+    Ok(expanded.into())
+}
+
+fn arbittach_struct_fields(derive_input: &DeriveInput) -> Result<Vec<&Field>, SynError> {
+    let Data::Struct(struct_tree) = &derive_input.data else {
+        return Err(SynError::new_spanned(
+            &derive_input.ident,
+            "must be implemented for struct",
+        ));
+    };
+    match &struct_tree.fields {
+        Fields::Named(named) => Ok(named.named.iter().collect()),
+        Fields::Unit => Ok(vec![]),
+        Fields::Unnamed(_) => Err(SynError::new_spanned(
+            &derive_input.ident,
+            "struct cannot use unnamed (tuple-style) fields",
+        )),
+    }
+}
+
+// values are kept as raw tokens until next top-level comma, so any expression can be written
+fn arbittach_find_attributes(attrs: &[Attribute]) -> Result<Vec<(Ident, Option<TokenStream2>)>, SynError> {
+    let mut entries = vec![];
+    for attr in attrs {
+        if !attr.path().is_ident("arbittach") {
+            continue;
+        }
+        attr.parse_args_with(|input: ParseStream| {
+            while !input.is_empty() {
+                let key: Ident = input.parse()?;
+                let value = if input.peek(Token![=]) {
+                    input.parse::<Token![=]>()?;
+                    let mut tokens = TokenStream2::new();
+                    while !input.is_empty() && !input.peek(Token![,]) {
+                        tokens.extend([input.parse::<TokenTree>()?]);
+                    }
+                    Some(tokens)
+                } else {
+                    None
+                };
+                entries.push((key, value));
+                if !input.is_empty() {
+                    input.parse::<Token![,]>()?;
+                }
+            }
+            Ok(())
+        })?;
+    }
+    Ok(entries)
+}
+
+fn arbittach_expect_value(key: &Ident, value: Option<TokenStream2>) -> Result<TokenStream2, SynError> {
+    match value {
+        Some(value) if !value.is_empty() => Ok(value),
+        _ => Err(SynError::new_spanned(key, "value expected")),
+    }
+}
+
+fn unraw(ident: &Ident) -> String {
+    ident.to_string().trim_start_matches("r#").to_string()
+}
+
+fn to_pascal_case(snake: &str) -> String {
+    snake
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
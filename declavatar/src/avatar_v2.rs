@@ -1,17 +1,24 @@
+pub mod analysis;
 pub mod data;
+pub mod error;
 pub mod log;
 mod transformer;
 
 use crate::{
     avatar_v2::{
         data::{attachment::schema::Attachment, avatar::Avatar},
+        error::SchemaError,
         transformer::compile_avatar,
     },
     decl_v2::data::avatar::DeclAvatar,
     log::{Logger, SerializedLog},
 };
 
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    fs::{read_dir, read_to_string},
+    path::Path,
+};
 
 #[derive(Debug, Clone)]
 pub struct Transformer {
@@ -25,9 +32,55 @@ impl Transformer {
         }
     }
 
-    pub fn register_arbittach_schema(&mut self, schema: Attachment) {
+    // re-registering identical schema is allowed, but different ones with same name are rejected
+    pub fn register_arbittach_schema(&mut self, schema: Attachment) -> Result<(), SchemaError> {
+        validate_arbittach_schema(&schema)?;
+        if let Some(registered) = self.arbittach_schemas.get(&schema.name) {
+            if registered.version != schema.version {
+                return Err(SchemaError::VersionConflict {
+                    name: schema.name,
+                    registered: registered.version,
+                    found: schema.version,
+                });
+            } else if registered != &schema {
+                return Err(SchemaError::DefinitionConflict {
+                    name: schema.name,
+                    version: schema.version,
+                });
+            }
+            return Ok(());
+        }
+
         let name = schema.name.clone();
         self.arbittach_schemas.insert(name, schema);
+        Ok(())
+    }
+
+    // loads all *.json files in the directory (non-recursive) in file name order
+    pub fn load_arbittach_schemas(&mut self, directory: impl AsRef<Path>) -> Result<usize, SchemaError> {
+        let directory = directory.as_ref();
+        let entries = read_dir(directory).map_err(|e| SchemaError::Io(directory.to_path_buf(), e))?;
+
+        let mut schema_paths = vec![];
+        for entry in entries {
+            let path = entry.map_err(|e| SchemaError::Io(directory.to_path_buf(), e))?.path();
+            if path.is_file() && path.extension().is_some_and(|ext| ext == "json") {
+                schema_paths.push(path);
+            }
+        }
+        schema_paths.sort();
+
+        for schema_path in &schema_paths {
+            self.load_arbittach_schema_file(schema_path)?;
+        }
+        Ok(schema_paths.len())
+    }
+
+    pub fn load_arbittach_schema_file(&mut self, path: impl AsRef<Path>) -> Result<(), SchemaError> {
+        let path = path.as_ref();
+        let schema_json = read_to_string(path).map_err(|e| SchemaError::Io(path.to_path_buf(), e))?;
+        let schema = serde_json::from_str(&schema_json).map_err(|e| SchemaError::Json(path.to_path_buf(), e))?;
+        self.register_arbittach_schema(schema)
     }
 
     pub fn transform_avatar(&self, avatar: DeclAvatar) -> TransformResult {
@@ -49,3 +102,38 @@ pub struct TransformResult {
     pub avatar: Option<Avatar>,
     pub logs: Vec<SerializedLog>,
 }
+
+// omitted parameters are filled from the front, so required ones cannot follow omittable ones
+fn validate_arbittach_schema(schema: &Attachment) -> Result<(), SchemaError> {
+    for property in &schema.properties {
+        let defaults = property
+            .parameters
+            .iter()
+            .map(|p| (&p.name, &p.value_type, &p.default))
+            .chain(property.keywords.iter().map(|k| (&k.name, &k.value_type, &k.default)));
+        for (item, value_type, default) in defaults {
+            if default.as_ref().is_some_and(|d| !value_type.accepts(d)) {
+                return Err(SchemaError::InvalidDefault {
+                    name: schema.name.clone(),
+                    property: property.name.clone(),
+                    item: item.clone(),
+                    expected: value_type.name().to_string(),
+                });
+            }
+        }
+
+        let mut omittable_found = false;
+        for parameter in &property.parameters {
+            if parameter.omittable() {
+                omittable_found = true;
+            } else if omittable_found {
+                return Err(SchemaError::RequiredAfterOmittable {
+                    name: schema.name.clone(),
+                    property: property.name.clone(),
+                    parameter: parameter.name.clone(),
+                });
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,68 @@
+pub mod diff;
+pub mod variants;
+pub mod xref;
+
+#[cfg(test)]
+mod fixture {
+    use crate::avatar_v2::data::{
+        avatar::{Avatar, AVATAR_FORMAT_VERSION},
+        contact::ContactReceiver,
+        layer::Layer,
+        menu::MenuItem,
+        parameter::{DeclaredParameter, ParameterDomain, ParameterScope, ParameterType},
+    };
+
+    /// Builds avatars for analysis tests without going through declarations.
+    pub struct AvatarBuilder {
+        avatar: Avatar,
+    }
+
+    impl AvatarBuilder {
+        pub fn new(name: &str) -> AvatarBuilder {
+            AvatarBuilder {
+                avatar: Avatar {
+                    format_version: AVATAR_FORMAT_VERSION,
+                    name: name.to_string(),
+                    exports: vec![],
+                    attachments: vec![],
+                    parameters: vec![],
+                    contact_receivers: vec![],
+                    assets: vec![],
+                    fx_controller: vec![],
+                    menu_items: vec![],
+                },
+            }
+        }
+
+        pub fn parameter(mut self, name: &str, value_type: ParameterType, scope: ParameterScope) -> AvatarBuilder {
+            self.avatar.parameters.push(DeclaredParameter {
+                name: name.to_string(),
+                value_type,
+                scope,
+                unique: false,
+                explicit_default: false,
+                domain: ParameterDomain::Unbounded,
+            });
+            self
+        }
+
+        pub fn contact_receiver(mut self, receiver: ContactReceiver) -> AvatarBuilder {
+            self.avatar.contact_receivers.push(receiver);
+            self
+        }
+
+        pub fn layer(mut self, layer: Layer) -> AvatarBuilder {
+            self.avatar.fx_controller.push(layer);
+            self
+        }
+
+        pub fn menu_item(mut self, menu_item: MenuItem) -> AvatarBuilder {
+            self.avatar.menu_items.push(menu_item);
+            self
+        }
+
+        pub fn build(self) -> Avatar {
+            self.avatar
+        }
+    }
+}
@@ -0,0 +1,597 @@
+use crate::avatar_v2::data::{
+    avatar::Avatar,
+    layer::{Layer, LayerAnimation, LayerContent, LayerRawAnimationKind, LayerRawBlendTreeType, Target},
+    menu::MenuItem,
+    parameter::{DeclaredParameter, ParameterScope, ParameterType},
+};
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AvatarDiff {
+    pub parameters: Vec<ParameterChange>,
+    pub menu_items: Vec<MenuChange>,
+    pub layers: Vec<LayerChange>,
+    // names of layers in both avatars, in old and new order; later layers take priority at runtime
+    pub layer_order: Option<(Vec<String>, Vec<String>)>,
+    pub synced_bits: SyncedBitsDelta,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", content = "content")]
+pub enum ParameterChange {
+    Added(DeclaredParameter),
+    Removed(DeclaredParameter),
+    Modified {
+        name: String,
+        value_type: Option<(ParameterType, ParameterType)>,
+        scope: Option<(ParameterScope, ParameterScope)>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", content = "content")]
+pub enum MenuChange {
+    Added(String),
+    Removed(String),
+    Modified(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", content = "content")]
+pub enum LayerChange {
+    Added(String),
+    Removed(String),
+    Modified {
+        name: String,
+        kind: Option<(String, String)>,
+        parameter: Option<(String, String)>,
+        options: Vec<OptionChange>,
+        transitions_changed: bool,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", content = "content")]
+pub enum OptionChange {
+    Added(String),
+    Removed(String),
+    Modified {
+        name: String,
+        index: Option<(usize, usize)>,
+        settings_changed: bool,
+        targets: Vec<TargetChange>,
+    },
+}
+
+// targets are identified by their driving keys
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", content = "content")]
+pub enum TargetChange {
+    Added(String),
+    Removed(String),
+    Modified(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct SyncedBitsDelta {
+    pub old: usize,
+    pub new: usize,
+}
+
+impl SyncedBitsDelta {
+    pub fn delta(&self) -> isize {
+        self.new as isize - self.old as isize
+    }
+}
+
+impl AvatarDiff {
+    pub fn compare(old: &Avatar, new: &Avatar) -> AvatarDiff {
+        AvatarDiff {
+            parameters: diff_parameters(&old.parameters, &new.parameters),
+            menu_items: diff_menu_items(&old.menu_items, &new.menu_items),
+            layers: diff_layers(&old.fx_controller, &new.fx_controller),
+            layer_order: diff_layer_order(&old.fx_controller, &new.fx_controller),
+            synced_bits: SyncedBitsDelta {
+                old: old.parameters.iter().map(|p| p.synced_bits()).sum(),
+                new: new.parameters.iter().map(|p| p.synced_bits()).sum(),
+            },
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parameters.is_empty()
+            && self.menu_items.is_empty()
+            && self.layers.is_empty()
+            && self.layer_order.is_none()
+            && self.synced_bits.old == self.synced_bits.new
+    }
+}
+
+fn diff_parameters(old: &[DeclaredParameter], new: &[DeclaredParameter]) -> Vec<ParameterChange> {
+    let old_map: BTreeMap<_, _> = old.iter().map(|p| (p.name.as_str(), p)).collect();
+    let new_map: BTreeMap<_, _> = new.iter().map(|p| (p.name.as_str(), p)).collect();
+
+    let mut changes = vec![];
+    for (name, old_parameter) in &old_map {
+        let Some(new_parameter) = new_map.get(name) else {
+            changes.push(ParameterChange::Removed((*old_parameter).clone()));
+            continue;
+        };
+        let value_type = (old_parameter.value_type != new_parameter.value_type)
+            .then_some((old_parameter.value_type, new_parameter.value_type));
+        let scope = (old_parameter.scope != new_parameter.scope).then_some((old_parameter.scope, new_parameter.scope));
+        if value_type.is_some() || scope.is_some() {
+            changes.push(ParameterChange::Modified {
+                name: name.to_string(),
+                value_type,
+                scope,
+            });
+        }
+    }
+    for (name, new_parameter) in &new_map {
+        if !old_map.contains_key(name) {
+            changes.push(ParameterChange::Added((*new_parameter).clone()));
+        }
+    }
+    changes
+}
+
+fn diff_menu_items(old: &[MenuItem], new: &[MenuItem]) -> Vec<MenuChange> {
+    let mut old_map = BTreeMap::new();
+    let mut new_map = BTreeMap::new();
+    flatten_menu_items("", old, &mut old_map);
+    flatten_menu_items("", new, &mut new_map);
+
+    let mut changes = vec![];
+    for (path, old_item) in &old_map {
+        match new_map.get(path) {
+            Some(new_item) if new_item != old_item => changes.push(MenuChange::Modified(path.clone())),
+            Some(_) => (),
+            None => changes.push(MenuChange::Removed(path.clone())),
+        }
+    }
+    for path in new_map.keys() {
+        if !old_map.contains_key(path) {
+            changes.push(MenuChange::Added(path.clone()));
+        }
+    }
+    changes
+}
+
+// submenus are compared by their children, so they are recorded without items
+pub(super) fn flatten_menu_items<'a>(
+    prefix: &str,
+    items: &'a [MenuItem],
+    flattened: &mut BTreeMap<String, Option<&'a MenuItem>>,
+) {
+    for item in items {
+        let name = match item {
+            MenuItem::SubMenu(mg) => &mg.name,
+            MenuItem::Button(mb) | MenuItem::Toggle(mb) => &mb.name,
+            MenuItem::Radial(mr) => &mr.name,
+            MenuItem::TwoAxis(mt) => &mt.name,
+            MenuItem::FourAxis(mf) => &mf.name,
+        };
+        let path = format!("{prefix}/{name}");
+        if let MenuItem::SubMenu(mg) = item {
+            flattened.insert(path.clone(), None);
+            flatten_menu_items(&path, &mg.items, flattened);
+        } else {
+            flattened.insert(path, Some(item));
+        }
+    }
+}
+
+fn diff_layers(old: &[Layer], new: &[Layer]) -> Vec<LayerChange> {
+    let old_map: BTreeMap<_, _> = old.iter().map(|l| (l.name.as_str(), l)).collect();
+    let new_map: BTreeMap<_, _> = new.iter().map(|l| (l.name.as_str(), l)).collect();
+
+    let mut changes = vec![];
+    for (name, old_layer) in &old_map {
+        let Some(new_layer) = new_map.get(name) else {
+            changes.push(LayerChange::Removed(name.to_string()));
+            continue;
+        };
+        if old_layer == new_layer {
+            continue;
+        }
+
+        let (old_kind, new_kind) = (layer_kind(old_layer), layer_kind(new_layer));
+        let kind = (old_kind != new_kind).then(|| (old_kind.to_string(), new_kind.to_string()));
+        let (old_parameter, new_parameter) = (layer_parameter(old_layer), layer_parameter(new_layer));
+        let parameter = (old_parameter != new_parameter).then(|| {
+            (
+                old_parameter.unwrap_or_default().to_string(),
+                new_parameter.unwrap_or_default().to_string(),
+            )
+        });
+        let transitions_changed = match (&old_layer.content, &new_layer.content) {
+            (
+                LayerContent::Raw {
+                    default_index: old_default,
+                    transitions: old_transitions,
+                    ..
+                },
+                LayerContent::Raw {
+                    default_index: new_default,
+                    transitions: new_transitions,
+                    ..
+                },
+            ) => old_default != new_default || old_transitions != new_transitions,
+            _ => false,
+        };
+        let options = diff_options(&layer_options(old_layer), &layer_options(new_layer));
+        if kind.is_none() && parameter.is_none() && options.is_empty() && !transitions_changed {
+            continue;
+        }
+        changes.push(LayerChange::Modified {
+            name: name.to_string(),
+            kind,
+            parameter,
+            options,
+            transitions_changed,
+        });
+    }
+    for name in new_map.keys() {
+        if !old_map.contains_key(name) {
+            changes.push(LayerChange::Added(name.to_string()));
+        }
+    }
+    changes
+}
+
+fn diff_layer_order(old: &[Layer], new: &[Layer]) -> Option<(Vec<String>, Vec<String>)> {
+    let common_names = |layers: &[Layer], others: &[Layer]| -> Vec<String> {
+        layers
+            .iter()
+            .filter(|l| others.iter().any(|o| o.name == l.name))
+            .map(|l| l.name.clone())
+            .collect()
+    };
+    let (old_order, new_order) = (common_names(old, new), common_names(new, old));
+    (old_order != new_order).then_some((old_order, new_order))
+}
+
+fn diff_options(old: &[LayerOption], new: &[LayerOption]) -> Vec<OptionChange> {
+    let old_map: BTreeMap<_, _> = old.iter().map(|o| (o.name.as_str(), o)).collect();
+    let new_map: BTreeMap<_, _> = new.iter().map(|o| (o.name.as_str(), o)).collect();
+
+    let mut changes = vec![];
+    for (name, old_option) in &old_map {
+        let Some(new_option) = new_map.get(name) else {
+            changes.push(OptionChange::Removed(name.to_string()));
+            continue;
+        };
+        let index = (old_option.index != new_option.index).then_some((old_option.index, new_option.index));
+        let settings_changed = old_option.settings != new_option.settings;
+        let targets = diff_targets(
+            &keyed_targets(&old_option.animations),
+            &keyed_targets(&new_option.animations),
+        );
+        if index.is_some() || settings_changed || !targets.is_empty() {
+            changes.push(OptionChange::Modified {
+                name: name.to_string(),
+                index,
+                settings_changed,
+                targets,
+            });
+        }
+    }
+    for name in new_map.keys() {
+        if !old_map.contains_key(name) {
+            changes.push(OptionChange::Added(name.to_string()));
+        }
+    }
+    changes
+}
+
+fn diff_targets(old: &BTreeMap<String, TargetValue>, new: &BTreeMap<String, TargetValue>) -> Vec<TargetChange> {
+    let mut changes = vec![];
+    for (key, old_target) in old {
+        match new.get(key) {
+            Some(new_target) if new_target != old_target => changes.push(TargetChange::Modified(key.clone())),
+            Some(_) => (),
+            None => changes.push(TargetChange::Removed(key.clone())),
+        }
+    }
+    for key in new.keys() {
+        if !old.contains_key(key) {
+            changes.push(TargetChange::Added(key.clone()));
+        }
+    }
+    changes
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TargetValue<'a> {
+    Target(&'a Target),
+    External(&'a str),
+}
+
+fn keyed_targets<'a>(animations: &[&'a LayerAnimation]) -> BTreeMap<String, TargetValue<'a>> {
+    let mut keyed = BTreeMap::new();
+    for (index, animation) in animations.iter().enumerate() {
+        // blend tree fields are distinguished by their index
+        let prefix = if animations.len() > 1 {
+            format!("field {index}: ")
+        } else {
+            String::new()
+        };
+        match animation {
+            LayerAnimation::Inline(targets) => {
+                for target in targets {
+                    keyed.insert(format!("{prefix}{}", target.driving_key()), TargetValue::Target(target));
+                }
+            }
+            LayerAnimation::KeyedInline(keyframes) => {
+                for keyframe in keyframes {
+                    for target in &keyframe.targets {
+                        let key = format!("{prefix}{} @ {}", target.driving_key(), keyframe.value);
+                        keyed.insert(key, TargetValue::Target(target));
+                    }
+                }
+            }
+            LayerAnimation::External(asset) => {
+                keyed.insert(format!("{prefix}external"), TargetValue::External(asset));
+            }
+        }
+    }
+    keyed
+}
+
+fn layer_kind(layer: &Layer) -> &'static str {
+    match layer.content {
+        LayerContent::Group { .. } => "group",
+        LayerContent::Switch { .. } => "switch",
+        LayerContent::Puppet { .. } => "puppet",
+        LayerContent::SwitchGate { .. } => "switch-gate",
+        LayerContent::Raw { .. } => "raw",
+    }
+}
+
+fn layer_parameter(layer: &Layer) -> Option<&str> {
+    match &layer.content {
+        LayerContent::Group { parameter, .. }
+        | LayerContent::Switch { parameter, .. }
+        | LayerContent::Puppet { parameter, .. } => Some(parameter),
+        LayerContent::SwitchGate { gate, .. } => Some(gate),
+        LayerContent::Raw { .. } => None,
+    }
+}
+
+// group options are indexed by their parameter values, and raw states by their positions
+struct LayerOption<'a> {
+    name: String,
+    index: usize,
+    animations: Vec<&'a LayerAnimation>,
+    settings: StateSettings<'a>,
+}
+
+impl<'a> LayerOption<'a> {
+    fn new(name: &str, index: usize, animations: Vec<&'a LayerAnimation>) -> LayerOption<'a> {
+        LayerOption {
+            name: name.to_string(),
+            index,
+            animations,
+            settings: StateSettings::None,
+        }
+    }
+}
+
+// raw state properties other than animations
+#[derive(Debug, Clone, PartialEq)]
+enum StateSettings<'a> {
+    None,
+    Clip {
+        speed: Option<f64>,
+        speed_by: Option<&'a str>,
+        time_by: Option<&'a str>,
+    },
+    BlendTree {
+        blend_type: LayerRawBlendTreeType,
+        params: &'a [String],
+        positions: Vec<[f64; 2]>,
+    },
+}
+
+fn layer_options(layer: &Layer) -> Vec<LayerOption<'_>> {
+    match &layer.content {
+        LayerContent::Group { default, options, .. } => [default]
+            .into_iter()
+            .chain(options)
+            .map(|o| LayerOption::new(&o.name, o.value, vec![&o.animation]))
+            .collect(),
+        LayerContent::Switch { disabled, enabled, .. } | LayerContent::SwitchGate { disabled, enabled, .. } => vec![
+            LayerOption::new("disabled", 0, vec![disabled]),
+            LayerOption::new("enabled", 1, vec![enabled]),
+        ],
+        LayerContent::Puppet { animation, .. } => vec![LayerOption::new("puppet", 0, vec![animation])],
+        LayerContent::Raw { states, .. } => states
+            .iter()
+            .enumerate()
+            .map(|(index, state)| match &state.animation {
+                LayerRawAnimationKind::Clip {
+                    animation,
+                    speed,
+                    speed_by,
+                    time_by,
+                } => LayerOption {
+                    settings: StateSettings::Clip {
+                        speed: *speed,
+                        speed_by: speed_by.as_deref(),
+                        time_by: time_by.as_deref(),
+                    },
+                    ..LayerOption::new(&state.name, index, vec![animation])
+                },
+                LayerRawAnimationKind::BlendTree {
+                    blend_type,
+                    params,
+                    fields,
+                } => LayerOption {
+                    settings: StateSettings::BlendTree {
+                        blend_type: *blend_type,
+                        params,
+                        positions: fields.iter().map(|f| f.position).collect(),
+                    },
+                    ..LayerOption::new(&state.name, index, fields.iter().map(|f| &f.animation).collect())
+                },
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::avatar_v2::{
+        analysis::{
+            diff::{diff_layer_order, diff_layers, AvatarDiff, LayerChange, MenuChange, OptionChange, ParameterChange},
+            fixture::AvatarBuilder,
+        },
+        data::{
+            layer::{Layer, LayerAnimation, LayerContent, LayerGroupOption, LayerRawAnimationKind, LayerRawState},
+            menu::{MenuBoolean, MenuItem},
+            parameter::{ParameterScope, ParameterType},
+        },
+    };
+
+    use pretty_assertions::assert_eq;
+
+    fn group_layer(name: &str, options: &[(&str, usize)]) -> Layer {
+        let option = |name: &str, value| LayerGroupOption {
+            name: name.to_string(),
+            value,
+            animation: LayerAnimation::Inline(vec![]),
+        };
+        Layer {
+            name: name.to_string(),
+            content: LayerContent::Group {
+                parameter: name.to_string(),
+                default: option("default", 0),
+                options: options.iter().map(|(n, v)| option(n, *v)).collect(),
+            },
+        }
+    }
+
+    fn raw_layer(speed: Option<f64>) -> Layer {
+        Layer {
+            name: "Raw".to_string(),
+            content: LayerContent::Raw {
+                default_index: 0,
+                states: vec![LayerRawState {
+                    name: "idle".to_string(),
+                    animation: LayerRawAnimationKind::Clip {
+                        animation: LayerAnimation::Inline(vec![]),
+                        speed,
+                        speed_by: None,
+                        time_by: None,
+                    },
+                }],
+                transitions: vec![],
+            },
+        }
+    }
+
+    #[test]
+    fn reports_parameters_and_menu_items() {
+        let toggle = MenuItem::Toggle(MenuBoolean {
+            name: "Hat".to_string(),
+            parameter: "Hat".to_string(),
+            value: ParameterType::Bool(true),
+        });
+        let old = AvatarBuilder::new("diff")
+            .parameter("Hat", ParameterType::BOOL_TYPE, ParameterScope::Local(false))
+            .menu_item(toggle)
+            .build();
+        let new = AvatarBuilder::new("diff")
+            .parameter("Hat", ParameterType::BOOL_TYPE, ParameterScope::Synced(false))
+            .parameter("Emote", ParameterType::INT_TYPE, ParameterScope::Synced(false))
+            .build();
+        assert!(AvatarDiff::compare(&old, &old).is_empty());
+
+        let diff = AvatarDiff::compare(&old, &new);
+        assert_eq!(
+            diff.parameters,
+            vec![
+                ParameterChange::Modified {
+                    name: "Hat".to_string(),
+                    value_type: None,
+                    scope: Some((ParameterScope::Local(false), ParameterScope::Synced(false))),
+                },
+                ParameterChange::Added(new.parameters[1].clone()),
+            ]
+        );
+        assert_eq!(diff.menu_items, vec![MenuChange::Removed("/Hat".to_string())]);
+        assert_eq!((diff.synced_bits.old, diff.synced_bits.new), (0, 9));
+        assert_eq!(diff.synced_bits.delta(), 9);
+    }
+
+    #[test]
+    fn reports_layer_order() {
+        let old = vec![group_layer("A", &[]), group_layer("B", &[]), group_layer("C", &[])];
+        let new = vec![group_layer("B", &[]), group_layer("A", &[]), group_layer("D", &[])];
+
+        assert_eq!(
+            diff_layers(&old, &new),
+            vec![
+                LayerChange::Removed("C".to_string()),
+                LayerChange::Added("D".to_string())
+            ]
+        );
+        assert_eq!(
+            diff_layer_order(&old, &new),
+            Some((
+                vec!["A".to_string(), "B".to_string()],
+                vec!["B".to_string(), "A".to_string()]
+            ))
+        );
+        // added and removed layers do not change the order of others
+        assert_eq!(diff_layer_order(&old, &new[..2]), diff_layer_order(&old[..2], &new));
+        assert_eq!(diff_layer_order(&old, &old[..2]), None);
+    }
+
+    #[test]
+    fn reports_option_indices() {
+        let old = vec![group_layer("A", &[("x", 1), ("y", 2)])];
+        let new = vec![group_layer("A", &[("x", 2), ("y", 1)])];
+
+        let option_change = |name: &str, index| OptionChange::Modified {
+            name: name.to_string(),
+            index: Some(index),
+            settings_changed: false,
+            targets: vec![],
+        };
+        assert_eq!(
+            diff_layers(&old, &new),
+            vec![LayerChange::Modified {
+                name: "A".to_string(),
+                kind: None,
+                parameter: None,
+                options: vec![option_change("x", (1, 2)), option_change("y", (2, 1))],
+                transitions_changed: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn reports_raw_state_settings() {
+        let changes = diff_layers(&[raw_layer(None)], &[raw_layer(Some(2.0))]);
+        assert_eq!(
+            changes,
+            vec![LayerChange::Modified {
+                name: "Raw".to_string(),
+                kind: None,
+                parameter: None,
+                options: vec![OptionChange::Modified {
+                    name: "idle".to_string(),
+                    index: None,
+                    settings_changed: true,
+                    targets: vec![],
+                }],
+                transitions_changed: false,
+            }]
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use crate::avatar_v2::{analysis::diff::flatten_menu_items, data::avatar::Avatar};
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VariantReport {
+    pub variants: Vec<String>,
+    pub parameters: Vec<VariantDifference>,
+    pub layers: Vec<VariantDifference>,
+    pub menu_items: Vec<VariantDifference>,
+    // in the same order as variants
+    pub synced_bits: Vec<usize>,
+}
+
+// items identical in all variants are not reported
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VariantDifference {
+    pub name: String,
+    pub present_in: Vec<String>,
+    // whether the definitions differ among variants containing the item
+    pub modified: bool,
+}
+
+impl VariantReport {
+    pub fn analyze<'a>(variants: impl IntoIterator<Item = (&'a str, &'a Avatar)>) -> VariantReport {
+        let (names, avatars): (Vec<_>, Vec<_>) = variants.into_iter().map(|(n, a)| (n.to_string(), a)).unzip();
+
+        let parameters: Vec<BTreeMap<_, _>> = avatars
+            .iter()
+            .map(|a| a.parameters.iter().map(|p| (p.name.clone(), p)).collect())
+            .collect();
+        let layers: Vec<BTreeMap<_, _>> = avatars
+            .iter()
+            .map(|a| a.fx_controller.iter().map(|l| (l.name.clone(), l)).collect())
+            .collect();
+        let menu_items: Vec<_> = avatars
+            .iter()
+            .map(|a| {
+                let mut flattened = BTreeMap::new();
+                flatten_menu_items("", &a.menu_items, &mut flattened);
+                flattened
+            })
+            .collect();
+
+        VariantReport {
+            parameters: compare_items(&names, &parameters),
+            layers: compare_items(&names, &layers),
+            menu_items: compare_items(&names, &menu_items),
+            synced_bits: avatars
+                .iter()
+                .map(|a| a.parameters.iter().map(|p| p.synced_bits()).sum())
+                .collect(),
+            variants: names,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parameters.is_empty()
+            && self.layers.is_empty()
+            && self.menu_items.is_empty()
+            && self.synced_bits.windows(2).all(|w| w[0] == w[1])
+    }
+}
+
+fn compare_items<T: PartialEq>(names: &[String], items: &[BTreeMap<String, T>]) -> Vec<VariantDifference> {
+    let keys: BTreeSet<_> = items.iter().flat_map(|m| m.keys()).collect();
+
+    let mut differences = vec![];
+    for key in keys {
+        let present: Vec<_> = names
+            .iter()
+            .zip(items)
+            .filter_map(|(name, m)| m.get(key).map(|item| (name, item)))
+            .collect();
+        let modified = present.windows(2).any(|w| w[0].1 != w[1].1);
+        if present.len() == names.len() && !modified {
+            continue;
+        }
+        differences.push(VariantDifference {
+            name: key.clone(),
+            present_in: present.into_iter().map(|(name, _)| name.clone()).collect(),
+            modified,
+        });
+    }
+    differences
+}
+
+#[cfg(test)]
+mod test {
+    use crate::avatar_v2::{
+        analysis::{
+            fixture::AvatarBuilder,
+            variants::{VariantDifference, VariantReport},
+        },
+        data::{
+            avatar::Avatar,
+            layer::{Layer, LayerAnimation, LayerContent},
+            menu::{MenuBoolean, MenuItem},
+            parameter::{ParameterScope, ParameterType},
+        },
+    };
+
+    use pretty_assertions::assert_eq;
+
+    // each parameter comes with a switch layer and a toggle of the same name
+    fn avatar(parameters: &[(&str, ParameterScope)]) -> Avatar {
+        parameters
+            .iter()
+            .fold(AvatarBuilder::new("variants"), |builder, (name, scope)| {
+                builder
+                    .parameter(name, ParameterType::BOOL_TYPE, *scope)
+                    .layer(Layer {
+                        name: name.to_string(),
+                        content: LayerContent::Switch {
+                            parameter: name.to_string(),
+                            disabled: LayerAnimation::Inline(vec![]),
+                            enabled: LayerAnimation::Inline(vec![]),
+                        },
+                    })
+                    .menu_item(MenuItem::Toggle(MenuBoolean {
+                        name: name.to_string(),
+                        parameter: name.to_string(),
+                        value: ParameterType::Bool(true),
+                    }))
+            })
+            .build()
+    }
+
+    #[test]
+    fn reports_items_missing_or_modified_in_some_variants() {
+        let pc = avatar(&[
+            ("Blush", ParameterScope::Synced(false)),
+            ("Hat", ParameterScope::Synced(false)),
+        ]);
+        let quest = avatar(&[("Hat", ParameterScope::Local(false))]);
+        let report = VariantReport::analyze([("pc", &pc), ("quest", &quest)]);
+
+        let only_pc = VariantDifference {
+            name: "Blush".to_string(),
+            present_in: vec!["pc".to_string()],
+            modified: false,
+        };
+        assert_eq!(report.variants, vec!["pc", "quest"]);
+        assert_eq!(
+            report.parameters,
+            vec![
+                only_pc.clone(),
+                VariantDifference {
+                    name: "Hat".to_string(),
+                    present_in: vec!["pc".to_string(), "quest".to_string()],
+                    modified: true,
+                },
+            ]
+        );
+        assert_eq!(report.layers, vec![only_pc.clone()]);
+        assert_eq!(
+            report.menu_items,
+            vec![VariantDifference {
+                name: "/Blush".to_string(),
+                ..only_pc
+            }]
+        );
+        assert_eq!(report.synced_bits, vec![2, 0]);
+        assert!(!report.is_empty());
+
+        assert!(VariantReport::analyze([("pc", &pc), ("desktop", &pc)]).is_empty());
+    }
+}
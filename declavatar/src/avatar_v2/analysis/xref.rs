@@ -0,0 +1,421 @@
+use crate::avatar_v2::data::{
+    avatar::Avatar,
+    contact::ContactReceiverType,
+    driver::ParameterDrive,
+    export::ExportItem,
+    layer::{LayerAnimation, LayerContent, LayerRawAnimationKind, LayerRawCondition, Target},
+    menu::MenuItem,
+};
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CrossReference {
+    pub parameters: BTreeMap<String, ParameterReferences>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ParameterReferences {
+    // false for VRChat/PhysBone/contact provided parameters
+    pub declared: bool,
+    pub menu_writers: Vec<String>,
+    pub contact_writers: Vec<ContactReference>,
+    pub drive_writers: Vec<DriveReference>,
+    pub drive_readers: Vec<DriveReference>,
+    pub layer_readers: Vec<LayerReference>,
+    pub guarded_gates: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DriveReference {
+    pub layer: String,
+    pub location: String,
+    // source for writers, target for readers
+    pub counterpart: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ContactReference {
+    pub receiver_type: ContactReceiverType,
+    pub root_object: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LayerReference {
+    pub layer: String,
+    pub location: Option<String>,
+    pub kind: LayerReadKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum LayerReadKind {
+    DrivenBy,
+    Condition,
+    BlendTree,
+    SpeedBy,
+    TimeBy,
+}
+
+impl CrossReference {
+    pub fn analyze(avatar: &Avatar) -> CrossReference {
+        let mut xref = CrossReference::default();
+        for parameter in &avatar.parameters {
+            xref.entry(&parameter.name).declared = true;
+        }
+
+        for receiver in &avatar.contact_receivers {
+            xref.entry(&receiver.parameter).contact_writers.push(ContactReference {
+                receiver_type: receiver.receiver_type,
+                root_object: receiver.root_object.clone(),
+            });
+        }
+
+        let mut menu_path = vec![];
+        for item in &avatar.menu_items {
+            xref.collect_menu_item(&mut menu_path, item);
+        }
+
+        for layer in &avatar.fx_controller {
+            let layer_name = &layer.name;
+            match &layer.content {
+                LayerContent::Group {
+                    parameter,
+                    default,
+                    options,
+                } => {
+                    xref.add_layer_read(parameter, layer_name, None, LayerReadKind::DrivenBy);
+                    for option in [default].into_iter().chain(options) {
+                        xref.collect_animation(layer_name, &option.name, &option.animation);
+                    }
+                }
+                LayerContent::Switch {
+                    parameter,
+                    disabled,
+                    enabled,
+                } => {
+                    xref.add_layer_read(parameter, layer_name, None, LayerReadKind::DrivenBy);
+                    xref.collect_animation(layer_name, "disabled", disabled);
+                    xref.collect_animation(layer_name, "enabled", enabled);
+                }
+                LayerContent::Puppet { parameter, animation } => {
+                    xref.add_layer_read(parameter, layer_name, None, LayerReadKind::DrivenBy);
+                    xref.collect_animation(layer_name, "puppet", animation);
+                }
+                LayerContent::SwitchGate { disabled, enabled, .. } => {
+                    xref.collect_animation(layer_name, "disabled", disabled);
+                    xref.collect_animation(layer_name, "enabled", enabled);
+                }
+                LayerContent::Raw {
+                    states, transitions, ..
+                } => {
+                    for state in states {
+                        let location = Some(state.name.clone());
+                        match &state.animation {
+                            LayerRawAnimationKind::Clip {
+                                animation,
+                                speed_by,
+                                time_by,
+                                ..
+                            } => {
+                                if let Some(speed_by) = speed_by {
+                                    xref.add_layer_read(speed_by, layer_name, location.clone(), LayerReadKind::SpeedBy);
+                                }
+                                if let Some(time_by) = time_by {
+                                    xref.add_layer_read(time_by, layer_name, location.clone(), LayerReadKind::TimeBy);
+                                }
+                                xref.collect_animation(layer_name, &state.name, animation);
+                            }
+                            LayerRawAnimationKind::BlendTree { params, fields, .. } => {
+                                for param in params {
+                                    xref.add_layer_read(param, layer_name, location.clone(), LayerReadKind::BlendTree);
+                                }
+                                for field in fields {
+                                    xref.collect_animation(layer_name, &state.name, &field.animation);
+                                }
+                            }
+                        }
+                    }
+                    for transition in transitions {
+                        let location = states.get(transition.from_index).map(|s| s.name.clone());
+                        for condition in &transition.conditions {
+                            let parameter = condition_parameter(condition);
+                            xref.add_layer_read(parameter, layer_name, location.clone(), LayerReadKind::Condition);
+                        }
+                    }
+                }
+            }
+        }
+
+        for export in &avatar.exports {
+            if let ExportItem::Guard { gate, parameter } = export {
+                xref.entry(parameter).guarded_gates.push(gate.clone());
+            }
+        }
+
+        xref
+    }
+
+    // edges from parameters read by a layer to parameters driven in the same layer
+    // self edges (e.g. switch layers resetting their own parameter) are excluded, as they do not affect others
+    pub fn drive_dependencies(&self) -> BTreeMap<&str, BTreeSet<&str>> {
+        let mut dependencies: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        for (read_name, read_refs) in &self.parameters {
+            for layer_read in &read_refs.layer_readers {
+                for (written_name, written_refs) in &self.parameters {
+                    if written_name != read_name
+                        && written_refs.drive_writers.iter().any(|w| w.layer == layer_read.layer)
+                    {
+                        dependencies.entry(read_name).or_default().insert(written_name);
+                    }
+                }
+            }
+            for drive_read in &read_refs.drive_readers {
+                match &drive_read.counterpart {
+                    Some(target) if target != read_name => {
+                        dependencies.entry(read_name).or_default().insert(target);
+                    }
+                    _ => (),
+                }
+            }
+        }
+        dependencies
+    }
+
+    fn entry(&mut self, parameter: &str) -> &mut ParameterReferences {
+        self.parameters.entry(parameter.to_string()).or_default()
+    }
+
+    fn collect_menu_item(&mut self, path: &mut Vec<String>, item: &MenuItem) {
+        let (name, parameters): (_, Vec<&str>) = match item {
+            MenuItem::SubMenu(group) => {
+                path.push(group.name.clone());
+                for child in &group.items {
+                    self.collect_menu_item(path, child);
+                }
+                path.pop();
+                return;
+            }
+            MenuItem::Button(mb) | MenuItem::Toggle(mb) => (&mb.name, vec![&mb.parameter]),
+            MenuItem::Radial(mr) => (&mr.name, vec![&mr.parameter]),
+            MenuItem::TwoAxis(mt) => (
+                &mt.name,
+                vec![&mt.horizontal_axis.parameter, &mt.vertical_axis.parameter],
+            ),
+            MenuItem::FourAxis(mf) => (
+                &mf.name,
+                vec![
+                    &mf.left_axis.parameter,
+                    &mf.right_axis.parameter,
+                    &mf.up_axis.parameter,
+                    &mf.down_axis.parameter,
+                ],
+            ),
+        };
+
+        let item_path = path.iter().chain([name]).cloned().collect::<Vec<_>>().join("/");
+        for parameter in parameters {
+            let writers = &mut self.entry(parameter).menu_writers;
+            if !writers.contains(&item_path) {
+                writers.push(item_path.clone());
+            }
+        }
+    }
+
+    fn collect_animation(&mut self, layer: &str, location: &str, animation: &LayerAnimation) {
+        let targets: Vec<&Target> = match animation {
+            LayerAnimation::Inline(targets) => targets.iter().collect(),
+            LayerAnimation::KeyedInline(keyframes) => keyframes.iter().flat_map(|kf| &kf.targets).collect(),
+            LayerAnimation::External(_) => return,
+        };
+
+        for target in targets {
+            let Target::ParameterDrive(drive) = target else {
+                continue;
+            };
+            let source = match drive {
+                ParameterDrive::Copy(source, _) | ParameterDrive::RangedCopy(source, _, _, _) => Some(source),
+                _ => None,
+            };
+            let target_name = drive.target_parameter();
+
+            self.entry(target_name).drive_writers.push(DriveReference {
+                layer: layer.to_string(),
+                location: location.to_string(),
+                counterpart: source.cloned(),
+            });
+            if let Some(source) = source {
+                self.entry(source).drive_readers.push(DriveReference {
+                    layer: layer.to_string(),
+                    location: location.to_string(),
+                    counterpart: Some(target_name.to_string()),
+                });
+            }
+        }
+    }
+
+    fn add_layer_read(&mut self, parameter: &str, layer: &str, location: Option<String>, kind: LayerReadKind) {
+        let reference = LayerReference {
+            layer: layer.to_string(),
+            location,
+            kind,
+        };
+        let readers = &mut self.entry(parameter).layer_readers;
+        if !readers.contains(&reference) {
+            readers.push(reference);
+        }
+    }
+}
+
+fn condition_parameter(condition: &LayerRawCondition) -> &str {
+    match condition {
+        LayerRawCondition::Be(p) => p,
+        LayerRawCondition::Not(p) => p,
+        LayerRawCondition::EqInt(p, _) => p,
+        LayerRawCondition::NeqInt(p, _) => p,
+        LayerRawCondition::GtInt(p, _) => p,
+        LayerRawCondition::LeInt(p, _) => p,
+        LayerRawCondition::GtFloat(p, _) => p,
+        LayerRawCondition::LeFloat(p, _) => p,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::avatar_v2::{
+        analysis::{
+            fixture::AvatarBuilder,
+            xref::{ContactReference, CrossReference, DriveReference, LayerReadKind, LayerReference},
+        },
+        data::{
+            contact::{ContactReceiver, ContactReceiverType},
+            driver::ParameterDrive,
+            layer::{Layer, LayerAnimation, LayerContent, Target},
+            parameter::{ParameterScope, ParameterType},
+        },
+    };
+
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use pretty_assertions::assert_eq;
+
+    fn contact_receiver(
+        parameter: &str,
+        receiver_type: ContactReceiverType,
+        root_object: Option<&str>,
+    ) -> ContactReceiver {
+        ContactReceiver {
+            parameter: parameter.to_string(),
+            receiver_type,
+            root_object: root_object.map(str::to_string),
+            radius: 0.1,
+            collision_tags: vec!["Hand".to_string()],
+            allow_self: true,
+            allow_others: true,
+            local_only: false,
+        }
+    }
+
+    fn switch_layer(parameter: &str, disabled: Vec<ParameterDrive>, enabled: Vec<ParameterDrive>) -> Layer {
+        let animation = |drives: Vec<ParameterDrive>| {
+            LayerAnimation::Inline(drives.into_iter().map(Target::ParameterDrive).collect())
+        };
+        Layer {
+            name: parameter.to_string(),
+            content: LayerContent::Switch {
+                parameter: parameter.to_string(),
+                disabled: animation(disabled),
+                enabled: animation(enabled),
+            },
+        }
+    }
+
+    #[test]
+    fn records_layer_readers_and_drive_writers() {
+        let avatar = AvatarBuilder::new("xref")
+            .parameter("Hat", ParameterType::BOOL_TYPE, ParameterScope::Synced(false))
+            .layer(switch_layer(
+                "Hat",
+                vec![],
+                vec![ParameterDrive::SetBool("Hood".to_string(), false)],
+            ))
+            .build();
+        let xref = CrossReference::analyze(&avatar);
+
+        let hat = &xref.parameters["Hat"];
+        assert!(hat.declared);
+        assert_eq!(
+            hat.layer_readers,
+            vec![LayerReference {
+                layer: "Hat".to_string(),
+                location: None,
+                kind: LayerReadKind::DrivenBy,
+            }]
+        );
+        let hood = &xref.parameters["Hood"];
+        assert!(!hood.declared);
+        assert_eq!(
+            hood.drive_writers,
+            vec![DriveReference {
+                layer: "Hat".to_string(),
+                location: "enabled".to_string(),
+                counterpart: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn records_contact_receivers_as_writers() {
+        let avatar = AvatarBuilder::new("xref")
+            .contact_receiver(contact_receiver(
+                "HeadContact",
+                ContactReceiverType::Constant,
+                Some("Head"),
+            ))
+            .contact_receiver(contact_receiver("HandProximity", ContactReceiverType::Proximity, None))
+            .build();
+        let xref = CrossReference::analyze(&avatar);
+
+        assert_eq!(
+            xref.parameters["HeadContact"].contact_writers,
+            vec![ContactReference {
+                receiver_type: ContactReceiverType::Constant,
+                root_object: Some("Head".to_string()),
+            }]
+        );
+        assert_eq!(
+            xref.parameters["HandProximity"].contact_writers,
+            vec![ContactReference {
+                receiver_type: ContactReceiverType::Proximity,
+                root_object: None,
+            }]
+        );
+        assert!(!xref.parameters["HeadContact"].declared);
+    }
+
+    #[test]
+    fn excludes_self_dependencies() {
+        let avatar = AvatarBuilder::new("xref")
+            .contact_receiver(contact_receiver("HeadContact", ContactReceiverType::Constant, None))
+            .layer(switch_layer(
+                "HeadContact",
+                vec![ParameterDrive::SetBool("HeadPatted".to_string(), false)],
+                vec![ParameterDrive::SetBool("HeadPatted".to_string(), true)],
+            ))
+            // resets its own parameter
+            .layer(switch_layer(
+                "Hat",
+                vec![],
+                vec![ParameterDrive::SetBool("Hat".to_string(), false)],
+            ))
+            .build();
+        let xref = CrossReference::analyze(&avatar);
+
+        assert_eq!(
+            xref.drive_dependencies(),
+            BTreeMap::from([("HeadContact", BTreeSet::from(["HeadPatted"]))])
+        );
+        assert_eq!(xref.parameters["Hat"].drive_writers[0].layer, "Hat");
+    }
+}
@@ -1,6 +1,7 @@
 pub mod asset;
 pub mod attachment;
 pub mod avatar;
+pub mod contact;
 pub mod controller;
 pub mod driver;
 pub mod export;
@@ -1,27 +1,29 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
-use serde::Serialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct Attachment {
     pub name: String,
     pub properties: Vec<Property>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct Property {
     pub name: String,
     pub parameters: Vec<Value>,
-    pub keywords: HashMap<String, Value>,
+    pub keywords: BTreeMap<String, Value>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type", content = "content")]
 pub enum Value {
     Null,
     List(Vec<Value>),
     Tuple(Vec<Value>),
-    // Map(HashMap<Value, Value>),
+    // serialized as key-value pairs in declaration order
+    Map(Vec<(Value, Value)>),
     Boolean(bool),
     Integer(i64),
     Float(f64),
@@ -30,6 +32,7 @@ pub enum Value {
     GameObject(String),
     Material(String),
     AnimationClip(String),
+    Parameter(String),
 }
 
 impl Value {
@@ -37,6 +40,7 @@ impl Value {
         match self {
             Value::List(_) => "list",
             Value::Tuple(_) => "tuple",
+            Value::Map(_) => "map",
             Value::Null => "null",
             Value::Boolean(_) => "boolean",
             Value::Integer(_) => "integer",
@@ -46,17 +50,34 @@ impl Value {
             Value::GameObject(_) => "game object",
             Value::Material(_) => "material",
             Value::AnimationClip(_) => "animation clip",
+            Value::Parameter(_) => "parameter",
         }
     }
 }
 
 pub mod schema {
+    use crate::avatar_v2::data::{attachment::Value, parameter::ParameterType};
+
+    use std::{
+        collections::{BTreeMap, HashMap},
+        iter::zip,
+    };
+
     use serde::{Deserialize, Serialize};
+    use serde_json::{json, Map as JsonMap, Value as JsonValue};
+
+    pub use declavatar_derive::{ArbittachProperty, ArbittachSchema};
+
+    const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
 
     #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct Attachment {
         pub name: String,
         pub properties: Vec<Property>,
+
+        // declarations can require minimum version with `:version`
+        #[serde(default)]
+        pub version: u32,
     }
 
     #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -67,10 +88,103 @@ pub mod schema {
         pub keywords: Vec<Keyword>,
     }
 
+    impl Attachment {
+        // describes compiled attachment output, not this schema file itself
+        pub fn to_json_schema(&self) -> JsonValue {
+            let property_schemas: Vec<_> = self.properties.iter().map(Property::to_json_schema).collect();
+            let mut properties_schema = json!({
+                "type": "array",
+                "items": { "oneOf": property_schemas },
+            });
+            let required_properties: Vec<_> = self
+                .properties
+                .iter()
+                .filter(|p| p.required)
+                .map(|p| json!({ "contains": { "properties": { "name": { "const": p.name } } } }))
+                .collect();
+            if !required_properties.is_empty() {
+                properties_schema["allOf"] = JsonValue::Array(required_properties);
+            }
+
+            json!({
+                "$schema": JSON_SCHEMA_DIALECT,
+                "title": self.name,
+                "type": "object",
+                "properties": {
+                    "name": { "const": self.name },
+                    "properties": properties_schema,
+                },
+                "required": ["name", "properties"],
+            })
+        }
+    }
+
+    impl Property {
+        // parameters after this position can be omitted
+        pub fn min_parameters(&self) -> usize {
+            self.parameters
+                .iter()
+                .rposition(|p| !p.omittable())
+                .map_or(0, |i| i + 1)
+        }
+
+        fn to_json_schema(&self) -> JsonValue {
+            // omitted parameters are filled with default values as far as possible
+            let min_parameters = self.min_parameters();
+            let min_items = min_parameters
+                + self.parameters[min_parameters..]
+                    .iter()
+                    .take_while(|p| p.default.is_some())
+                    .count();
+            let parameter_schemas: Vec<_> = self.parameters.iter().map(|p| p.value_type.to_json_schema()).collect();
+
+            let mut keyword_schemas = JsonMap::new();
+            let mut present_keywords = vec![];
+            for keyword in &self.keywords {
+                keyword_schemas.insert(keyword.name.clone(), keyword.value_type.to_json_schema());
+                if keyword.required || keyword.default.is_some() {
+                    present_keywords.push(keyword.name.clone());
+                }
+            }
+
+            json!({
+                "type": "object",
+                "properties": {
+                    "name": { "const": self.name },
+                    "parameters": {
+                        "type": "array",
+                        "prefixItems": parameter_schemas,
+                        "items": false,
+                        "minItems": min_items,
+                    },
+                    "keywords": {
+                        "type": "object",
+                        "properties": keyword_schemas,
+                        "required": present_keywords,
+                        "additionalProperties": false,
+                    },
+                },
+                "required": ["name", "parameters", "keywords"],
+            })
+        }
+    }
+
     #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct Parameter {
         pub name: String,
         pub value_type: ValueType,
+
+        // trailing parameters can be omitted if they are optional or have default values
+        #[serde(default)]
+        pub optional: bool,
+        #[serde(default)]
+        pub default: Option<Value>,
+    }
+
+    impl Parameter {
+        pub fn omittable(&self) -> bool {
+            self.optional || self.default.is_some()
+        }
     }
 
     #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -78,6 +192,9 @@ pub mod schema {
         pub name: String,
         pub required: bool,
         pub value_type: ValueType,
+
+        #[serde(default)]
+        pub default: Option<Value>,
     }
 
     #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -97,6 +214,26 @@ pub mod schema {
         GameObject,
         Material,
         AnimationClip,
+        Enum(Vec<String>),
+        // any parameter type if None
+        Parameter(Option<ParameterValueType>),
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    pub enum ParameterValueType {
+        Int,
+        Float,
+        Bool,
+    }
+
+    impl ParameterValueType {
+        pub const fn parameter_type(self) -> ParameterType {
+            match self {
+                ParameterValueType::Int => ParameterType::INT_TYPE,
+                ParameterValueType::Float => ParameterType::FLOAT_TYPE,
+                ParameterValueType::Bool => ParameterType::BOOL_TYPE,
+            }
+        }
     }
 
     impl ValueType {
@@ -116,7 +253,202 @@ pub mod schema {
                 ValueType::GameObject => "game object",
                 ValueType::Material => "material",
                 ValueType::AnimationClip => "animation clip",
+                ValueType::Enum(_) => "enum",
+                ValueType::Parameter(_) => "parameter",
             }
         }
+
+        // whether compiled value can be produced for this type
+        pub fn accepts(&self, value: &Value) -> bool {
+            match (self, value) {
+                (ValueType::Any, _) => true,
+                (ValueType::OneOf(types), value) => types.iter().any(|t| t.accepts(value)),
+                (ValueType::List(item_type), Value::List(items)) => items.iter().all(|i| item_type.accepts(i)),
+                (ValueType::Tuple(item_types), Value::Tuple(items)) => {
+                    item_types.len() == items.len() && zip(item_types, items).all(|(t, i)| t.accepts(i))
+                }
+                (ValueType::Map(key_type, value_type), Value::Map(pairs)) => {
+                    pairs.iter().all(|(k, v)| key_type.accepts(k) && value_type.accepts(v))
+                }
+                (ValueType::Null, Value::Null)
+                | (ValueType::Boolean, Value::Boolean(_))
+                | (ValueType::Integer, Value::Integer(_))
+                | (ValueType::Float, Value::Float(_))
+                | (ValueType::String, Value::String(_))
+                | (ValueType::GameObject, Value::GameObject(_))
+                | (ValueType::Material, Value::Material(_))
+                | (ValueType::AnimationClip, Value::AnimationClip(_))
+                | (ValueType::Parameter(_), Value::Parameter(_)) => true,
+                (ValueType::Vector(length), Value::Vector(values)) => *length == values.len(),
+                (ValueType::Enum(variants), Value::String(variant)) => variants.contains(variant),
+                _ => false,
+            }
+        }
+
+        // matches serialized form of Value
+        pub fn to_json_schema(&self) -> JsonValue {
+            match self {
+                ValueType::Any => json!({}),
+                ValueType::OneOf(types) => {
+                    json!({ "oneOf": types.iter().map(ValueType::to_json_schema).collect::<Vec<_>>() })
+                }
+                ValueType::List(item_type) => tagged_schema(
+                    "List",
+                    Some(json!({ "type": "array", "items": item_type.to_json_schema() })),
+                ),
+                ValueType::Tuple(item_types) => tagged_schema(
+                    "Tuple",
+                    Some(json!({
+                        "type": "array",
+                        "prefixItems": item_types.iter().map(ValueType::to_json_schema).collect::<Vec<_>>(),
+                        "items": false,
+                        "minItems": item_types.len(),
+                    })),
+                ),
+                ValueType::Map(key_type, value_type) => tagged_schema(
+                    "Map",
+                    Some(json!({
+                        "type": "array",
+                        "items": {
+                            "type": "array",
+                            "prefixItems": [key_type.to_json_schema(), value_type.to_json_schema()],
+                            "items": false,
+                            "minItems": 2,
+                        },
+                    })),
+                ),
+                ValueType::Null => tagged_schema("Null", None),
+                ValueType::Boolean => tagged_schema("Boolean", Some(json!({ "type": "boolean" }))),
+                ValueType::Integer => tagged_schema("Integer", Some(json!({ "type": "integer" }))),
+                ValueType::Float => tagged_schema("Float", Some(json!({ "type": "number" }))),
+                ValueType::String => tagged_schema("String", Some(json!({ "type": "string" }))),
+                ValueType::Vector(length) => tagged_schema(
+                    "Vector",
+                    Some(json!({
+                        "type": "array",
+                        "items": { "type": "number" },
+                        "minItems": length,
+                        "maxItems": length,
+                    })),
+                ),
+                ValueType::GameObject => tagged_schema("GameObject", Some(json!({ "type": "string" }))),
+                ValueType::Material => tagged_schema("Material", Some(json!({ "type": "string" }))),
+                ValueType::AnimationClip => tagged_schema("AnimationClip", Some(json!({ "type": "string" }))),
+                ValueType::Enum(variants) => tagged_schema("String", Some(json!({ "enum": variants }))),
+                ValueType::Parameter(_) => tagged_schema("Parameter", Some(json!({ "type": "string" }))),
+            }
+        }
+    }
+
+    fn tagged_schema(tag: &str, content: Option<JsonValue>) -> JsonValue {
+        match content {
+            Some(content) => json!({
+                "type": "object",
+                "properties": { "type": { "const": tag }, "content": content },
+                "required": ["type", "content"],
+            }),
+            None => json!({
+                "type": "object",
+                "properties": { "type": { "const": tag } },
+                "required": ["type"],
+            }),
+        }
+    }
+
+    // implemented by #[derive(ArbittachSchema)]; fields of the struct become properties
+    pub trait ArbittachSchema {
+        fn arbittach_schema() -> Attachment;
+    }
+
+    // implemented by #[derive(ArbittachProperty)]; fields become parameters or keywords
+    pub trait ArbittachProperty {
+        const REQUIRED: bool = true;
+
+        fn parameters() -> Vec<Parameter>;
+        fn keywords() -> Vec<Keyword>;
+    }
+
+    impl<T: ArbittachProperty> ArbittachProperty for Option<T> {
+        const REQUIRED: bool = false;
+
+        fn parameters() -> Vec<Parameter> {
+            T::parameters()
+        }
+
+        fn keywords() -> Vec<Keyword> {
+            T::keywords()
+        }
+    }
+
+    // Rust types that correspond to a value type
+    pub trait ArbittachValue {
+        const OPTIONAL: bool = false;
+
+        fn value_type() -> ValueType;
+    }
+
+    macro_rules! arbittach_value_impl {
+        ($value_type:expr => $($t:ty),*) => {
+            $(
+                impl ArbittachValue for $t {
+                    fn value_type() -> ValueType {
+                        $value_type
+                    }
+                }
+            )*
+        };
     }
+
+    arbittach_value_impl!(ValueType::Any => Value);
+    arbittach_value_impl!(ValueType::Null => ());
+    arbittach_value_impl!(ValueType::Boolean => bool);
+    arbittach_value_impl!(ValueType::Integer => i8, i16, i32, i64, u8, u16, u32);
+    arbittach_value_impl!(ValueType::Float => f32, f64);
+    arbittach_value_impl!(ValueType::String => String, &str);
+
+    impl<T: ArbittachValue> ArbittachValue for Option<T> {
+        const OPTIONAL: bool = true;
+
+        fn value_type() -> ValueType {
+            T::value_type()
+        }
+    }
+
+    impl<T: ArbittachValue> ArbittachValue for Vec<T> {
+        fn value_type() -> ValueType {
+            ValueType::List(Box::new(T::value_type()))
+        }
+    }
+
+    impl<const N: usize> ArbittachValue for [f64; N] {
+        fn value_type() -> ValueType {
+            ValueType::Vector(N)
+        }
+    }
+
+    impl<K: ArbittachValue, V: ArbittachValue> ArbittachValue for BTreeMap<K, V> {
+        fn value_type() -> ValueType {
+            ValueType::Map(Box::new(K::value_type()), Box::new(V::value_type()))
+        }
+    }
+
+    impl<K: ArbittachValue, V: ArbittachValue, S> ArbittachValue for HashMap<K, V, S> {
+        fn value_type() -> ValueType {
+            ValueType::Map(Box::new(K::value_type()), Box::new(V::value_type()))
+        }
+    }
+
+    macro_rules! arbittach_value_tuple_impl {
+        ($($t:ident),*) => {
+            impl<$($t: ArbittachValue),*> ArbittachValue for ($($t,)*) {
+                fn value_type() -> ValueType {
+                    ValueType::Tuple(vec![$($t::value_type()),*])
+                }
+            }
+        };
+    }
+
+    arbittach_value_tuple_impl!(A, B);
+    arbittach_value_tuple_impl!(A, B, C);
+    arbittach_value_tuple_impl!(A, B, C, D);
 }
@@ -1,17 +1,30 @@
 use crate::avatar_v2::data::{
-    asset::Asset, attachment::Attachment, export::ExportItem, layer::Layer, menu::MenuItem,
+    asset::Asset, attachment::Attachment, contact::ContactReceiver, export::ExportItem, layer::Layer, menu::MenuItem,
     parameter::DeclaredParameter,
 };
 
-use serde::Serialize;
+use schemars::{schema::RootSchema, schema_for, JsonSchema};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize)]
+// bumped whenever the serialized shape of Avatar changes
+pub const AVATAR_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct Avatar {
+    pub format_version: u32,
     pub name: String,
     pub exports: Vec<ExportItem>,
     pub attachments: Vec<Attachment>,
     pub parameters: Vec<DeclaredParameter>,
+    pub contact_receivers: Vec<ContactReceiver>,
     pub assets: Vec<Asset>,
     pub fx_controller: Vec<Layer>,
     pub menu_items: Vec<MenuItem>,
 }
+
+impl Avatar {
+    // checked in as declavatar/schema/avatar.schema.json
+    pub fn json_schema() -> RootSchema {
+        schema_for!(Avatar)
+    }
+}
@@ -0,0 +1,43 @@
+use crate::{avatar_v2::data::parameter::ParameterType, decl_v2::data::parameter::DeclContactReceiverKind};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ContactReceiver {
+    pub parameter: String,
+    pub receiver_type: ContactReceiverType,
+    pub root_object: Option<String>,
+    pub radius: f64,
+    pub collision_tags: Vec<String>,
+    pub allow_self: bool,
+    pub allow_others: bool,
+    pub local_only: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum ContactReceiverType {
+    Constant,
+    OnEnter,
+    Proximity,
+}
+
+impl ContactReceiverType {
+    pub fn value_type(self) -> ParameterType {
+        match self {
+            ContactReceiverType::Constant => ParameterType::BOOL_TYPE,
+            ContactReceiverType::OnEnter => ParameterType::BOOL_TYPE,
+            ContactReceiverType::Proximity => ParameterType::FLOAT_TYPE,
+        }
+    }
+}
+
+impl From<DeclContactReceiverKind> for ContactReceiverType {
+    fn from(value: DeclContactReceiverKind) -> Self {
+        match value {
+            DeclContactReceiverKind::Constant => ContactReceiverType::Constant,
+            DeclContactReceiverKind::OnEnter => ContactReceiverType::OnEnter,
+            DeclContactReceiverKind::Proximity => ContactReceiverType::Proximity,
+        }
+    }
+}
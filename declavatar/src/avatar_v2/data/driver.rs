@@ -1,9 +1,10 @@
-use serde::Serialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
 use crate::decl_v2::data::driver::DeclTrackingTarget;
 
 // TODO: parameter name should be combined, but separated for compatibility
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type", content = "content")]
 pub enum ParameterDrive {
     SetInt(String, u8),
@@ -35,13 +36,13 @@ impl ParameterDrive {
     }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct TrackingControl {
     pub animation_desired: bool,
     pub target: TrackingTarget,
 }
 
-#[derive(Debug, Clone, Copy, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub enum TrackingTarget {
     Head,
     Hip,
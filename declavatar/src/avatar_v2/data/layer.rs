@@ -3,15 +3,16 @@ use crate::{
     decl_v2::data::layer::DeclMaterialValue,
 };
 
-use serde::Serialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct Layer {
     pub name: String,
     pub content: LayerContent,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type")]
 pub enum LayerContent {
     Group {
@@ -40,20 +41,20 @@ pub enum LayerContent {
     },
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct LayerGroupOption {
     pub name: String,
     pub value: usize,
     pub animation: LayerAnimation,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct LayerPuppetKeyframe {
     pub value: f64,
     pub targets: Vec<Target>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type", content = "content")]
 pub enum Target {
     Shape {
@@ -79,7 +80,7 @@ pub enum Target {
     TrackingControl(TrackingControl),
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type", content = "content")]
 pub enum MaterialValue {
     Float(f64),
@@ -88,13 +89,13 @@ pub enum MaterialValue {
     Vector([f64; 4]),
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct LayerRawState {
     pub name: String,
     pub animation: LayerRawAnimationKind,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type", content = "content")]
 pub enum LayerRawAnimationKind {
     Clip {
@@ -110,7 +111,7 @@ pub enum LayerRawAnimationKind {
     },
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub enum LayerRawBlendTreeType {
     Linear,
     Simple2D,
@@ -118,13 +119,13 @@ pub enum LayerRawBlendTreeType {
     Cartesian2D,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct LayerRawField {
     pub animation: LayerAnimation,
     pub position: [f64; 2],
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct LayerRawTransition {
     pub from_index: usize,
     pub target_index: usize,
@@ -132,7 +133,7 @@ pub struct LayerRawTransition {
     pub conditions: Vec<LayerRawCondition>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type", content = "content")]
 pub enum LayerRawCondition {
     Be(String),
@@ -145,7 +146,7 @@ pub enum LayerRawCondition {
     LeFloat(String, f64),
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type", content = "content")]
 pub enum LayerAnimation {
     Inline(Vec<Target>),
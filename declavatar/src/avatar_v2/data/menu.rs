@@ -1,8 +1,9 @@
 use crate::avatar_v2::data::parameter::ParameterType;
 
-use serde::Serialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type", content = "content")]
 pub enum MenuItem {
     SubMenu(MenuGroup),
@@ -13,33 +14,33 @@ pub enum MenuItem {
     FourAxis(MenuFourAxis),
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct MenuGroup {
     pub name: String,
     pub items: Vec<MenuItem>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct MenuBoolean {
     pub name: String,
     pub parameter: String,
     pub value: ParameterType,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct MenuRadial {
     pub name: String,
     pub parameter: String,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct MenuTwoAxis {
     pub name: String,
     pub horizontal_axis: BiAxis,
     pub vertical_axis: BiAxis,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct MenuFourAxis {
     pub name: String,
     pub left_axis: UniAxis,
@@ -48,14 +49,14 @@ pub struct MenuFourAxis {
     pub down_axis: UniAxis,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct BiAxis {
     pub parameter: String,
     pub label_positive: String,
     pub label_negative: String,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct UniAxis {
     pub parameter: String,
     pub label: String,
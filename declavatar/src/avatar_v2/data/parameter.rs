@@ -1,8 +1,12 @@
-use crate::decl_v2::data::parameter::{DeclParameterReference, DeclPhysBoneParameterKind, DeclProvidedParameterKind};
+use crate::{
+    avatar_v2::data::contact::ContactReceiver,
+    decl_v2::data::parameter::{DeclParameterReference, DeclPhysBoneParameterKind, DeclProvidedParameterKind},
+};
 
-use serde::Serialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type", content = "default")]
 pub enum ParameterType {
     Int(u8),
@@ -33,7 +37,7 @@ impl ParameterType {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type", content = "save")]
 pub enum ParameterScope {
     Internal,
@@ -67,23 +71,75 @@ impl ParameterScope {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", content = "value")]
+pub enum ParameterDomain {
+    #[default]
+    Unbounded,
+    Enum(Vec<String>),
+    Range(Option<f64>, Option<f64>),
+}
+
+impl ParameterDomain {
+    pub fn enum_index(&self, value_name: &str) -> Option<usize> {
+        match self {
+            ParameterDomain::Enum(values) => values.iter().position(|v| v == value_name),
+            ParameterDomain::Range(_, _) | ParameterDomain::Unbounded => None,
+        }
+    }
+
+    pub fn contains(&self, value: f64) -> bool {
+        match self {
+            ParameterDomain::Enum(values) => value >= 0.0 && value < values.len() as f64,
+            ParameterDomain::Range(min, max) => {
+                value >= min.unwrap_or(f64::NEG_INFINITY) && value <= max.unwrap_or(f64::INFINITY)
+            }
+            ParameterDomain::Unbounded => true,
+        }
+    }
+
+    pub fn bounds(&self) -> (Option<f64>, Option<f64>) {
+        match self {
+            ParameterDomain::Enum(values) => (Some(0.0), Some(values.len() as f64 - 1.0)),
+            ParameterDomain::Range(min, max) => (*min, *max),
+            ParameterDomain::Unbounded => (None, None),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct DeclaredParameter {
     pub name: String,
     pub value_type: ParameterType,
     pub scope: ParameterScope,
     pub unique: bool,
     pub explicit_default: bool,
+
+    // absent in outputs written before domains were serialized
+    #[serde(default)]
+    pub domain: ParameterDomain,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+impl DeclaredParameter {
+    // bits consumed in VRChat synced parameter memory
+    pub fn synced_bits(&self) -> usize {
+        match (self.scope, self.value_type) {
+            (ParameterScope::Synced(_), ParameterType::Bool(_)) => 1,
+            (ParameterScope::Synced(_), ParameterType::Int(_) | ParameterType::Float(_)) => 8,
+            (ParameterScope::Internal | ParameterScope::Local(_), _) => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "kind", content = "value")]
 pub enum ProvidedParameter {
     PhysBone(String),
+    Contact(ContactReceiver),
     Vrchat(VrchatParameterKind),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub enum VrchatParameterKind {
     IsLocal,
     Viseme,
@@ -273,6 +329,7 @@ impl Parameter {
             Parameter::Declared(declared) => &declared.name,
             Parameter::Provided(ProvidedParameter::Vrchat(vrc_param)) => vrc_param.parameter_name(),
             Parameter::Provided(ProvidedParameter::PhysBone(prefix)) => prefix,
+            Parameter::Provided(ProvidedParameter::Contact(receiver)) => &receiver.parameter,
         }
     }
 }
@@ -282,12 +339,14 @@ pub struct QualifiedParameter {
     pub name: String,
     pub value_type: ParameterType,
     pub unique: bool,
+    pub domain: ParameterDomain,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ParameterQuery {
     Declared(String),
     PhysBone(String, PhysBoneParameterKind),
+    Contact(String),
     Vrchat(VrchatParameterKind),
 }
 
@@ -298,12 +357,14 @@ impl ParameterQuery {
                 name: pn.name.clone(),
                 value_type: pn.value_type,
                 unique: pn.unique,
+                domain: pn.domain.clone(),
             }),
             (ParameterQuery::Vrchat(qk), Parameter::Provided(ProvidedParameter::Vrchat(pk))) if qk == pk => {
                 Some(QualifiedParameter {
                     name: pk.parameter_name().to_string(),
                     value_type: pk.value_type(),
                     unique: false,
+                    domain: ParameterDomain::Unbounded,
                 })
             }
             (ParameterQuery::PhysBone(qp, kind), Parameter::Provided(ProvidedParameter::PhysBone(pp))) if qp == pp => {
@@ -311,6 +372,17 @@ impl ParameterQuery {
                     name: format!("{pp}{}", kind.parameter_suffix()),
                     value_type: kind.value_type(),
                     unique: false,
+                    domain: ParameterDomain::Unbounded,
+                })
+            }
+            (ParameterQuery::Contact(qn), Parameter::Provided(ProvidedParameter::Contact(receiver)))
+                if qn == &receiver.parameter =>
+            {
+                Some(QualifiedParameter {
+                    name: receiver.parameter.clone(),
+                    value_type: receiver.receiver_type.value_type(),
+                    unique: false,
+                    domain: ParameterDomain::Unbounded,
                 })
             }
             _ => None,
@@ -322,6 +394,7 @@ impl ParameterQuery {
             ParameterQuery::Declared(name) => name,
             ParameterQuery::Vrchat(kind) => kind.parameter_name(),
             ParameterQuery::PhysBone(prefix, _) => prefix,
+            ParameterQuery::Contact(name) => name,
         }
     }
 }
@@ -329,8 +402,11 @@ impl ParameterQuery {
 impl From<DeclParameterReference> for ParameterQuery {
     fn from(value: DeclParameterReference) -> Self {
         match value {
-            DeclParameterReference::Primitive(name) => ParameterQuery::Declared(name),
+            DeclParameterReference::Primitive(name) | DeclParameterReference::Global(name) => {
+                ParameterQuery::Declared(name)
+            }
             DeclParameterReference::PhysBone(name, kind) => ParameterQuery::PhysBone(name, kind.into()),
+            DeclParameterReference::Contact(name) => ParameterQuery::Contact(name),
             DeclParameterReference::Provided(kind) => ParameterQuery::Vrchat(kind.into()),
         }
     }
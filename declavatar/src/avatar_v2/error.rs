@@ -0,0 +1,34 @@
+use std::{io::Error as IoError, path::PathBuf};
+
+use serde_json::Error as JsonError;
+use thiserror::Error as ThisError;
+
+#[derive(Debug, ThisError)]
+pub enum SchemaError {
+    #[error("failed to read schema {0:?}: {1}")]
+    Io(PathBuf, IoError),
+
+    #[error("failed to parse schema {0:?}: {1}")]
+    Json(PathBuf, JsonError),
+
+    #[error("schema {name} is already registered with version {registered} (found version {found})")]
+    VersionConflict { name: String, registered: u32, found: u32 },
+
+    #[error("schema {name} version {version} is already registered with different definition")]
+    DefinitionConflict { name: String, version: u32 },
+
+    #[error("default value of {property}/{item} in schema {name} is not {expected}")]
+    InvalidDefault {
+        name: String,
+        property: String,
+        item: String,
+        expected: String,
+    },
+
+    #[error("required parameter {property}/{parameter} in schema {name} follows omittable one")]
+    RequiredAfterOmittable {
+        name: String,
+        property: String,
+        parameter: String,
+    },
+}
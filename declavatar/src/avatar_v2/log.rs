@@ -18,6 +18,16 @@ pub enum Log {
     ParameterScopeRequirement(String, String),
     #[log_error("parameter.not_writable")]
     ParameterNotWritable(String),
+    #[log_error("parameter.enum_invalid")]
+    ParameterEnumInvalid(String),
+    #[log_error("parameter.enum_not_found")]
+    ParameterEnumValueNotFound(String, String),
+    #[log_error("parameter.enum_out_of_range")]
+    ParameterEnumOutOfRange(String, i64),
+    #[log_error("parameter.invalid_range")]
+    ParameterInvalidRange(String),
+    #[log_warn("parameter.value_out_of_range")]
+    ParameterValueOutOfRange(String, String),
 
     #[log_error("asset.incompatible_declaration")]
     IncompatibleAssetDeclaration(String),
@@ -61,6 +71,10 @@ pub enum Log {
     LayerOptionNotFound(String),
     #[log_error("layer.option_must_exclusive")]
     LayerOptionMustBeExclusive,
+    #[log_warn("layer.conflicting_writers")]
+    LayerConflictingWriters(String, String),
+    #[log_warn("layer.unknown_overlap")]
+    LayerUnknownOverlap(String, String),
 
     #[log_error("menu.invalid_drive")]
     MenuInvalidDrive,
@@ -79,6 +93,9 @@ pub enum ArbittachError {
     #[error("unknown attachment")]
     UnknownAttachment,
 
+    #[error("schema version too old; {required} required, {found} registered")]
+    SchemaVersionTooOld { required: u32, found: u32 },
+
     #[error("unknown property: {0}")]
     UnknownProperty(String),
 
@@ -90,4 +107,16 @@ pub enum ArbittachError {
 
     #[error("insufficient properties: {unmet_properties:?}")]
     Insufficient { unmet_properties: Vec<String> },
+
+    #[error("unknown keyword: {0}")]
+    UnknownKeyword(String),
+
+    #[error("insufficient keywords: {unmet_keywords:?}")]
+    InsufficientKeywords { unmet_keywords: Vec<String> },
+
+    #[error("unknown enum value: {0}")]
+    UnknownEnumValue(String),
+
+    #[error("duplicate map key: {0}")]
+    DuplicateMapKey(String),
 }
@@ -1,20 +1,28 @@
 use crate::{
     avatar_v2::{
-        data::attachment::{
-            schema::{Attachment as AttachmentSchema, Property as PropertySchema, ValueType},
-            Attachment, Property, Value,
+        data::{
+            asset::AssetType,
+            attachment::{
+                schema::{Attachment as AttachmentSchema, Property as PropertySchema, ValueType},
+                Attachment, Property, Value,
+            },
+            parameter::ParameterQuery,
         },
         log::{ArbittachError, Log},
-        transformer::{failure, success, Compiled},
+        transformer::{failure, success, Compiled, FirstPassData},
     },
     decl_v2::data::attachment::{DeclAttachment, DeclAttachmentProperty, DeclAttachmentValue, DeclAttachments},
     log::Logger,
 };
 
-use std::{collections::HashMap, iter::zip};
+use std::{
+    collections::{BTreeMap, HashMap},
+    iter::zip,
+};
 
 pub fn compile_attachment_blocks(
     logger: &Logger<Log>,
+    first_pass: &FirstPassData,
     schemas: &HashMap<String, AttachmentSchema>,
     attachment_blocks: Vec<DeclAttachments>,
 ) -> Compiled<Vec<Attachment>> {
@@ -22,7 +30,7 @@ pub fn compile_attachment_blocks(
     for (index, decl_attachments) in attachment_blocks.into_iter().enumerate() {
         let logger = logger.with_context(format!("attachments block {index}"));
         for decl_attachment in decl_attachments.attachments {
-            let Some(group) = compile_attachment(&logger, schemas, decl_attachment) else {
+            let Some(group) = compile_attachment(&logger, first_pass, schemas, decl_attachment) else {
                 continue;
             };
             attachment_groups.push(group);
@@ -34,6 +42,7 @@ pub fn compile_attachment_blocks(
 
 fn compile_attachment(
     logger: &Logger<Log>,
+    first_pass: &FirstPassData,
     schemas: &HashMap<String, AttachmentSchema>,
     decl_attachment: DeclAttachment,
 ) -> Compiled<Attachment> {
@@ -45,6 +54,15 @@ fn compile_attachment(
         logger.log(Log::Arbittach(ArbittachError::UnknownAttachment));
         return failure();
     };
+    if let Some(required) = decl_attachment.version {
+        if schema.version < required {
+            logger.log(Log::Arbittach(ArbittachError::SchemaVersionTooOld {
+                required,
+                found: schema.version,
+            }));
+            return failure();
+        }
+    }
     let mut unset_properties: HashMap<&str, &PropertySchema> =
         schema.properties.iter().map(|p| (p.name.as_str(), p)).collect();
     for decl_property in decl_attachment.properties {
@@ -55,7 +73,7 @@ fn compile_attachment(
             continue;
         };
 
-        let Some(property) = compile_property(&logger, property_schema, decl_property) else {
+        let Some(property) = compile_property(&logger, first_pass, property_schema, decl_property) else {
             continue;
         };
         properties.push(property);
@@ -77,28 +95,65 @@ fn compile_attachment(
 
 fn compile_property(
     logger: &Logger<Log>,
+    first_pass: &FirstPassData,
     property_schema: &PropertySchema,
     decl_property: DeclAttachmentProperty,
 ) -> Compiled<Property> {
     let mut parameters = vec![];
-    let keywords = HashMap::new();
+    let mut keywords = BTreeMap::new();
 
     let logger = logger.with_context(format!("property {}", decl_property.name));
-    if property_schema.parameters.len() != decl_property.parameters.len() {
+    let max_parameters = property_schema.parameters.len();
+    let min_parameters = property_schema.min_parameters();
+    let given_parameters = decl_property.parameters.len();
+    if given_parameters < min_parameters || given_parameters > max_parameters {
         logger.log(Log::Arbittach(ArbittachError::LengthMismatch {
-            expected: property_schema.parameters.len(),
-            found: decl_property.parameters.len(),
+            expected: if given_parameters < min_parameters {
+                min_parameters
+            } else {
+                max_parameters
+            },
+            found: given_parameters,
         }));
     }
 
     for (dprop, sprop) in zip(decl_property.parameters, &property_schema.parameters) {
-        let Some(value) = compile_value(&logger, &sprop.value_type, dprop) else {
+        let Some(value) = compile_value(&logger, first_pass, &sprop.value_type, dprop) else {
             continue;
         };
         parameters.push(value);
     }
+    // omitted parameters are filled until one without default value appears
+    for sprop in property_schema.parameters.iter().skip(given_parameters) {
+        let Some(default_value) = &sprop.default else {
+            break;
+        };
+        parameters.push(default_value.clone());
+    }
 
-    // TODO: parse keywords
+    let mut decl_keywords = decl_property.keywords;
+    let mut unmet_keywords = vec![];
+    for keyword_schema in &property_schema.keywords {
+        let Some(decl_value) = decl_keywords.remove(&keyword_schema.name) else {
+            if let Some(default_value) = &keyword_schema.default {
+                keywords.insert(keyword_schema.name.clone(), default_value.clone());
+            } else if keyword_schema.required {
+                unmet_keywords.push(keyword_schema.name.clone());
+            }
+            continue;
+        };
+        let Some(value) = compile_value(&logger, first_pass, &keyword_schema.value_type, decl_value) else {
+            continue;
+        };
+        keywords.insert(keyword_schema.name.clone(), value);
+    }
+    for unknown_keyword in decl_keywords.into_keys() {
+        logger.log(Log::Arbittach(ArbittachError::UnknownKeyword(unknown_keyword)));
+    }
+    if !unmet_keywords.is_empty() {
+        logger.log(Log::Arbittach(ArbittachError::InsufficientKeywords { unmet_keywords }));
+        return failure();
+    }
 
     success(Property {
         name: decl_property.name,
@@ -107,7 +162,12 @@ fn compile_property(
     })
 }
 
-fn compile_value(logger: &Logger<Log>, expected_type: &ValueType, decl_value: DeclAttachmentValue) -> Compiled<Value> {
+fn compile_value(
+    logger: &Logger<Log>,
+    first_pass: &FirstPassData,
+    expected_type: &ValueType,
+    decl_value: DeclAttachmentValue,
+) -> Compiled<Value> {
     let value = match decl_value {
         DeclAttachmentValue::Null if matches!(expected_type, ValueType::Null | ValueType::Any) => Value::Null,
         DeclAttachmentValue::Boolean(v) if matches!(expected_type, ValueType::Boolean | ValueType::Any) => {
@@ -120,15 +180,43 @@ fn compile_value(logger: &Logger<Log>, expected_type: &ValueType, decl_value: De
         DeclAttachmentValue::String(v) if matches!(expected_type, ValueType::String | ValueType::Any) => {
             Value::String(v)
         }
+        DeclAttachmentValue::String(v) if matches!(expected_type, ValueType::Enum(_)) => match expected_type {
+            ValueType::Enum(variants) if variants.contains(&v) => Value::String(v),
+            _ => {
+                logger.log(Log::Arbittach(ArbittachError::UnknownEnumValue(v)));
+                return failure();
+            }
+        },
         DeclAttachmentValue::GameObject(v) if matches!(expected_type, ValueType::GameObject | ValueType::Any) => {
             Value::GameObject(v)
         }
         DeclAttachmentValue::Material(v) if matches!(expected_type, ValueType::Material | ValueType::Any) => {
+            first_pass.find_asset(logger, &v, AssetType::Material)?;
             Value::Material(v)
         }
         DeclAttachmentValue::AnimationClip(v) if matches!(expected_type, ValueType::AnimationClip | ValueType::Any) => {
+            first_pass.find_asset(logger, &v, AssetType::Animation)?;
             Value::AnimationClip(v)
         }
+        DeclAttachmentValue::Parameter(parameter) => match expected_type {
+            ValueType::Parameter(Some(parameter_type)) => {
+                let query = ParameterQuery::from(parameter);
+                let qualified = first_pass.find_read_parameter(logger, &query, parameter_type.parameter_type())?;
+                Value::Parameter(qualified.name)
+            }
+            ValueType::Parameter(None) | ValueType::Any => {
+                let query = ParameterQuery::from(parameter);
+                let qualified = first_pass.find_untyped_parameter(logger, &query)?;
+                Value::Parameter(qualified.name)
+            }
+            _ => {
+                logger.log(Log::Arbittach(ArbittachError::TypeMismatch {
+                    found: "parameter".to_string(),
+                    expected: expected_type.name().to_string(),
+                }));
+                return failure();
+            }
+        },
 
         DeclAttachmentValue::Vector(values) => match expected_type {
             ValueType::Vector(length) if *length == values.len() => Value::Vector(values),
@@ -153,14 +241,14 @@ fn compile_value(logger: &Logger<Log>, expected_type: &ValueType, decl_value: De
             ValueType::Any => {
                 let any_values = untyped_list
                     .into_iter()
-                    .flat_map(|uv| compile_value(logger, &ValueType::Any, uv))
+                    .flat_map(|uv| compile_value(logger, first_pass, &ValueType::Any, uv))
                     .collect();
                 Value::List(any_values)
             }
             ValueType::List(item_type) => {
                 let Some(typed_values) = untyped_list
                     .into_iter()
-                    .map(|uv| compile_value(logger, item_type, uv))
+                    .map(|uv| compile_value(logger, first_pass, item_type, uv))
                     .collect::<Option<Vec<_>>>()
                 else {
                     // type error has been already logged at this point
@@ -177,7 +265,7 @@ fn compile_value(logger: &Logger<Log>, expected_type: &ValueType, decl_value: De
                     return failure();
                 }
                 let Some(typed_values) = zip(untyped_list, item_types)
-                    .map(|(uv, t)| compile_value(logger, t, uv))
+                    .map(|(uv, t)| compile_value(logger, first_pass, t, uv))
                     .collect::<Option<Vec<_>>>()
                 else {
                     // type error has been already logged at this point
@@ -194,6 +282,20 @@ fn compile_value(logger: &Logger<Log>, expected_type: &ValueType, decl_value: De
             }
         },
 
+        DeclAttachmentValue::Map(untyped_pairs) => match expected_type {
+            ValueType::Any => compile_map(logger, first_pass, &ValueType::Any, &ValueType::Any, untyped_pairs)?,
+            ValueType::Map(key_type, value_type) => {
+                compile_map(logger, first_pass, key_type, value_type, untyped_pairs)?
+            }
+            _ => {
+                logger.log(Log::Arbittach(ArbittachError::TypeMismatch {
+                    found: "map".to_string(),
+                    expected: expected_type.name().to_string(),
+                }));
+                return failure();
+            }
+        },
+
         _ => {
             logger.log(Log::Arbittach(ArbittachError::TypeMismatch {
                 found: "unexpected type value".to_string(),
@@ -204,3 +306,23 @@ fn compile_value(logger: &Logger<Log>, expected_type: &ValueType, decl_value: De
     };
     success(value)
 }
+
+fn compile_map(
+    logger: &Logger<Log>,
+    first_pass: &FirstPassData,
+    key_type: &ValueType,
+    value_type: &ValueType,
+    untyped_pairs: Vec<(DeclAttachmentValue, DeclAttachmentValue)>,
+) -> Compiled<Value> {
+    let mut pairs: Vec<(Value, Value)> = vec![];
+    for (untyped_key, untyped_value) in untyped_pairs {
+        let key = compile_value(logger, first_pass, key_type, untyped_key)?;
+        let value = compile_value(logger, first_pass, value_type, untyped_value)?;
+        if pairs.iter().any(|(k, _)| k == &key) {
+            logger.log(Log::Arbittach(ArbittachError::DuplicateMapKey(format!("{key:?}"))));
+            return failure();
+        }
+        pairs.push((key, value));
+    }
+    success(Value::Map(pairs))
+}
@@ -1,6 +1,10 @@
 use crate::{
     avatar_v2::{
-        data::{attachment::schema::Attachment, avatar::Avatar, parameter::Parameter},
+        data::{
+            attachment::schema::Attachment,
+            avatar::{Avatar, AVATAR_FORMAT_VERSION},
+            parameter::{Parameter, ProvidedParameter},
+        },
         log::Log,
         transformer::{
             asset::compile_assets_blocks,
@@ -39,32 +43,36 @@ pub fn compile_avatar(
     let parameters = compile_parameters_blocks(&logger, avatar.parameters_blocks)?;
     let assets = compile_assets_blocks(&logger, avatar.assets_blocks)?;
     let exports_first_pass = first_pass_exports_blocks(&logger, &avatar.exports_blocks)?;
-    let fx_first_pass = first_pass_fx_controller_blocks(&logger, &avatar.fx_controllers)?;
+    let fx_first_pass = first_pass_fx_controller_blocks(&logger, &parameters, &avatar.fx_controllers)?;
     let first_pass = FirstPassData::new(parameters, assets, exports_first_pass, fx_first_pass);
 
     // second pass
     let exports = compile_exports_blocks(&logger, &first_pass, avatar.exports_blocks)?;
     let fx_controller = compile_fx_controller_blocks(&logger, &first_pass, avatar.fx_controllers)?;
     let menu_items = compile_menu(&logger, &first_pass, avatar.menu_blocks)?;
-    let attachments = compile_attachment_blocks(&logger, attachment_schemas, avatar.attachment_blocks)?;
+    let attachments = compile_attachment_blocks(&logger, &first_pass, attachment_schemas, avatar.attachment_blocks)?;
 
     if logger.erroneous() {
         return failure();
     }
 
     let (parameters, assets) = first_pass.take_back();
-    let parameters = parameters
-        .into_iter()
-        .filter_map(|p| match p {
-            Parameter::Declared(dp) => Some(dp),
-            _ => None,
-        })
-        .collect();
+    let mut declared_parameters = vec![];
+    let mut contact_receivers = vec![];
+    for parameter in parameters {
+        match parameter {
+            Parameter::Declared(dp) => declared_parameters.push(dp),
+            Parameter::Provided(ProvidedParameter::Contact(receiver)) => contact_receivers.push(receiver),
+            _ => (),
+        }
+    }
     success(Avatar {
+        format_version: AVATAR_FORMAT_VERSION,
         name,
         exports,
         attachments,
-        parameters,
+        parameters: declared_parameters,
+        contact_receivers,
         assets,
         fx_controller,
         menu_items,
@@ -1,6 +1,9 @@
 use crate::{
     avatar_v2::{
-        data::layer::Layer,
+        data::{
+            layer::{Layer, LayerAnimation, LayerContent, LayerRawAnimationKind, Target},
+            parameter::Parameter,
+        },
         log::Log,
         transformer::{
             layer::{
@@ -14,17 +17,20 @@ use crate::{
     log::Logger,
 };
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 
 pub fn first_pass_fx_controller_blocks(
     logger: &Logger<Log>,
+    parameters: &[Parameter],
     fx_controller_blocks: &[DeclFxController],
 ) -> Compiled<Vec<DeclaredLayer>> {
     let mut declared_layers = vec![];
     for decl_fx_controller in fx_controller_blocks {
         for decl_layer in &decl_fx_controller.layers {
             let declared_layer = match decl_layer {
-                DeclControllerLayer::Group(decl_group_layer) => first_pass_group_layer(logger, decl_group_layer),
+                DeclControllerLayer::Group(decl_group_layer) => {
+                    first_pass_group_layer(logger, parameters, decl_group_layer)
+                }
                 DeclControllerLayer::Switch(decl_switch_layer) => first_pass_switch_layer(logger, decl_switch_layer),
                 DeclControllerLayer::Puppet(decl_puppet_layer) => first_pass_puppet_layer(logger, decl_puppet_layer),
                 DeclControllerLayer::Raw(decl_raw_layer) => first_pass_raw_layer(logger, decl_raw_layer),
@@ -44,10 +50,12 @@ pub fn compile_fx_controller_blocks(
     fx_controller_blocks: Vec<DeclFxController>,
 ) -> Compiled<Vec<Layer>> {
     let mut layers = vec![];
+    let mut allowed_overlaps = vec![];
     let mut used_group_names: HashSet<String> = HashSet::new();
     for (index, decl_fx_controller) in fx_controller_blocks.into_iter().enumerate() {
         let logger = logger.with_context(format!("fx-controller {index}"));
         for decl_layer in decl_fx_controller.layers {
+            let allow_overlap: BTreeSet<_> = decl_layer.allow_overlap().iter().cloned().collect();
             let layer = match decl_layer {
                 DeclControllerLayer::Group(decl_group_layer) => {
                     compile_group_layer(&logger, first_pass, decl_group_layer)
@@ -71,8 +79,72 @@ pub fn compile_fx_controller_blocks(
             }
 
             layers.push(layer);
+            allowed_overlaps.push(allow_overlap);
         }
     }
 
+    check_conflicting_writers(logger, &layers, &allowed_overlaps);
     success(layers)
 }
+
+// later layers take priority, so writers are listed in layer order
+fn check_conflicting_writers(logger: &Logger<Log>, layers: &[Layer], allowed_overlaps: &[BTreeSet<String>]) {
+    let mut writers: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    for (layer, allowed) in layers.iter().zip(allowed_overlaps) {
+        let keys = animated_keys(layer);
+        for unknown_key in allowed.difference(&keys) {
+            logger.log(Log::LayerUnknownOverlap(layer.name.clone(), unknown_key.clone()));
+        }
+        for key in keys {
+            if allowed.contains(&key) {
+                continue;
+            }
+            writers.entry(key).or_default().push(&layer.name);
+        }
+    }
+
+    for (key, layer_names) in writers {
+        if layer_names.len() > 1 {
+            logger.log(Log::LayerConflictingWriters(key, layer_names.join(" < ")));
+        }
+    }
+}
+
+fn animated_keys(layer: &Layer) -> BTreeSet<String> {
+    let animations: Vec<&LayerAnimation> = match &layer.content {
+        LayerContent::Group { default, options, .. } => {
+            [default].into_iter().chain(options).map(|o| &o.animation).collect()
+        }
+        LayerContent::Switch { disabled, enabled, .. } | LayerContent::SwitchGate { disabled, enabled, .. } => {
+            vec![disabled, enabled]
+        }
+        LayerContent::Puppet { animation, .. } => vec![animation],
+        LayerContent::Raw { states, .. } => states
+            .iter()
+            .flat_map(|state| match &state.animation {
+                LayerRawAnimationKind::Clip { animation, .. } => vec![animation],
+                LayerRawAnimationKind::BlendTree { fields, .. } => fields.iter().map(|f| &f.animation).collect(),
+            })
+            .collect(),
+    };
+
+    // parameter drives and tracking controls are not animated properties
+    let mut keys = BTreeSet::new();
+    for animation in animations {
+        let targets: Vec<&Target> = match animation {
+            LayerAnimation::Inline(targets) => targets.iter().collect(),
+            LayerAnimation::KeyedInline(keyframes) => keyframes.iter().flat_map(|kf| &kf.targets).collect(),
+            LayerAnimation::External(_) => continue,
+        };
+        for target in targets {
+            if let Target::Shape { .. }
+            | Target::Object { .. }
+            | Target::Material { .. }
+            | Target::MaterialProperty { .. } = target
+            {
+                keys.insert(target.driving_key());
+            }
+        }
+    }
+    keys
+}
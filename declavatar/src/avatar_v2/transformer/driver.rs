@@ -5,7 +5,11 @@ use crate::{
             parameter::ParameterType,
         },
         log::Log,
-        transformer::{failure, success, Compiled, FirstPassData, UnsetValue},
+        transformer::{
+            failure,
+            parameter::{check_float_domain, check_int_domain, resolve_enum_value},
+            success, Compiled, FirstPassData, UnsetValue,
+        },
     },
     decl_v2::data::driver::{DeclParameterDrive, DeclTrackingControl},
     log::Logger,
@@ -39,15 +43,23 @@ pub fn compile_parameter_drive(
         }
         DeclParameterDrive::SetInt { parameter, value } => {
             let qualified = first_pass.find_writable_parameter(logger, &parameter.into(), ParameterType::INT_TYPE)?;
+            check_int_domain(logger, &qualified, value)?;
             ParameterDrive::SetInt(qualified.name, value as u8)
         }
+        DeclParameterDrive::SetEnum { parameter, value } => {
+            let qualified = first_pass.find_writable_parameter(logger, &parameter.into(), ParameterType::INT_TYPE)?;
+            let index = resolve_enum_value(logger, &qualified, &value)?;
+            ParameterDrive::SetInt(qualified.name, index)
+        }
         DeclParameterDrive::SetBool { parameter, value } => {
             let qualified = first_pass.find_writable_parameter(logger, &parameter.into(), ParameterType::BOOL_TYPE)?;
             ParameterDrive::SetBool(qualified.name, unset_value.replace_bool(value))
         }
         DeclParameterDrive::SetFloat { parameter, value } => {
             let qualified = first_pass.find_writable_parameter(logger, &parameter.into(), ParameterType::FLOAT_TYPE)?;
-            ParameterDrive::SetFloat(qualified.name, unset_value.replace_f64(value))
+            let value = unset_value.replace_f64(value);
+            check_float_domain(logger, &qualified, value)?;
+            ParameterDrive::SetFloat(qualified.name, value)
         }
         DeclParameterDrive::AddInt { parameter, value } => {
             let qualified = first_pass.find_writable_parameter(logger, &parameter.into(), ParameterType::INT_TYPE)?;
@@ -59,6 +71,8 @@ pub fn compile_parameter_drive(
         }
         DeclParameterDrive::RandomInt { parameter, range } => {
             let qualified = first_pass.find_writable_parameter(logger, &parameter.into(), ParameterType::INT_TYPE)?;
+            check_int_domain(logger, &qualified, range.0 as i64)?;
+            check_int_domain(logger, &qualified, range.1 as i64)?;
             ParameterDrive::RandomInt(qualified.name, range)
         }
         DeclParameterDrive::RandomBool { parameter, value } => {
@@ -67,10 +81,12 @@ pub fn compile_parameter_drive(
         }
         DeclParameterDrive::RandomFloat { parameter, range } => {
             let qualified = first_pass.find_writable_parameter(logger, &parameter.into(), ParameterType::FLOAT_TYPE)?;
+            check_float_domain(logger, &qualified, range.0)?;
+            check_float_domain(logger, &qualified, range.1)?;
             ParameterDrive::RandomFloat(qualified.name, range)
         }
         DeclParameterDrive::Copy { from, to, range } => {
-            let qualified_from = first_pass.find_writable_parameter(logger, &from.into(), ParameterType::FLOAT_TYPE)?;
+            let qualified_from = first_pass.find_read_parameter(logger, &from.into(), ParameterType::FLOAT_TYPE)?;
             let qualified_to = first_pass.find_writable_parameter(logger, &to.into(), ParameterType::FLOAT_TYPE)?;
             if let Some(range) = range {
                 ParameterDrive::RangedCopy(qualified_from.name, qualified_to.name, range.0, range.1)
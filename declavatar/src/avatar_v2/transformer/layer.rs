@@ -6,12 +6,14 @@ use crate::{
                 Layer, LayerAnimation, LayerContent, LayerGroupOption, LayerPuppetKeyframe, LayerRawAnimationKind,
                 LayerRawBlendTreeType, LayerRawCondition, LayerRawField, LayerRawState, LayerRawTransition, Target,
             },
-            parameter::ParameterType,
+            parameter::{Parameter, ParameterDomain, ParameterQuery, ParameterType},
         },
         log::Log,
         transformer::{
             driver::{compile_parameter_drive, compile_tracking_control},
-            failure, success, Compiled, DeclaredLayer, DeclaredLayerType, FirstPassData, UnsetValue,
+            failure,
+            parameter::{check_int_domain, check_threshold_domain, resolve_enum_value},
+            success, Compiled, DeclaredLayer, DeclaredLayerType, FirstPassData, UnsetValue,
         },
     },
     decl_v2::data::layer::{
@@ -23,6 +25,7 @@ use crate::{
 };
 
 use std::{
+    cmp::Ordering,
     collections::BTreeMap,
     iter::{once, Once},
     vec::IntoIter as VecIntoIter,
@@ -30,20 +33,43 @@ use std::{
 
 use either::{Either, Left, Right};
 
-pub fn first_pass_group_layer(_logger: &Logger<Log>, decl_group_layer: &DeclGroupLayer) -> Compiled<DeclaredLayer> {
+pub fn first_pass_group_layer(
+    _logger: &Logger<Log>,
+    parameters: &[Parameter],
+    decl_group_layer: &DeclGroupLayer,
+) -> Compiled<DeclaredLayer> {
+    let query: ParameterQuery = decl_group_layer.driven_by.clone().into();
+    let domain = parameters
+        .iter()
+        .find_map(|p| query.qualify_match(p))
+        .map(|q| q.domain)
+        .unwrap_or_default();
+
     // if it compiles, order will be preserved
     let option_names = decl_group_layer
         .options
         .iter()
         .enumerate()
-        .flat_map(|(di, o)| o.kind.as_selection().map(|(n, i)| (n, i.unwrap_or(di + 1))))
+        .flat_map(|(di, o)| {
+            let (n, i) = o.kind.as_selection()?;
+            group_option_value(&domain, &n, i, di).map(|v| (n, v))
+        })
         .collect();
     success(DeclaredLayer {
         name: decl_group_layer.name.clone(),
-        layer_type: DeclaredLayerType::Group(decl_group_layer.driven_by.clone().into(), option_names),
+        layer_type: DeclaredLayerType::Group(query, option_names),
     })
 }
 
+// options driven by enum parameters take the index of the value with the same name
+fn group_option_value(domain: &ParameterDomain, name: &str, explicit: Option<usize>, position: usize) -> Option<usize> {
+    match (explicit, domain) {
+        (Some(value), _) => Some(value),
+        (None, ParameterDomain::Enum(_)) => domain.enum_index(name),
+        (None, ParameterDomain::Range(..)) | (None, ParameterDomain::Unbounded) => Some(position + 1),
+    }
+}
+
 pub fn first_pass_switch_layer(_logger: &Logger<Log>, decl_switch_layer: &DeclSwitchLayer) -> Compiled<DeclaredLayer> {
     match (&decl_switch_layer.driven_by, &decl_switch_layer.with_gate) {
         (Some(db), None) => success(DeclaredLayer {
@@ -109,11 +135,14 @@ pub fn compile_group_layer(
         else {
             continue;
         };
-        options.push(LayerGroupOption {
-            name,
-            value: explicit_index.unwrap_or(index + 1),
-            animation,
-        });
+        let Some(value) = group_option_value(&qualified.domain, &name, explicit_index, index) else {
+            logger.log(Log::ParameterEnumValueNotFound(qualified.name.clone(), name));
+            continue;
+        };
+        if check_int_domain(&logger, &qualified, value as i64).is_none() {
+            continue;
+        }
+        options.push(LayerGroupOption { name, value, animation });
     }
 
     match decl_group_layer.copy_mode {
@@ -638,17 +667,47 @@ fn compile_raw_condition(
         DeclRawLayerTransitionCondition::Int(query, order, value) => {
             let qualified = first_pass.find_read_parameter(logger, &query.into(), ParameterType::INT_TYPE)?;
             match order {
-                DeclRawLayerTransitionOrdering::Equal => LayerRawCondition::EqInt(qualified.name, value),
-                DeclRawLayerTransitionOrdering::NotEqual => LayerRawCondition::NeqInt(qualified.name, value),
-                DeclRawLayerTransitionOrdering::Greater => LayerRawCondition::GtInt(qualified.name, value),
-                DeclRawLayerTransitionOrdering::Lesser => LayerRawCondition::LeInt(qualified.name, value),
+                DeclRawLayerTransitionOrdering::Equal => {
+                    check_int_domain(logger, &qualified, value)?;
+                    LayerRawCondition::EqInt(qualified.name, value)
+                }
+                DeclRawLayerTransitionOrdering::NotEqual => {
+                    check_int_domain(logger, &qualified, value)?;
+                    LayerRawCondition::NeqInt(qualified.name, value)
+                }
+                DeclRawLayerTransitionOrdering::Greater => {
+                    check_threshold_domain(logger, &qualified, Ordering::Greater, value as f64)?;
+                    LayerRawCondition::GtInt(qualified.name, value)
+                }
+                DeclRawLayerTransitionOrdering::Lesser => {
+                    check_threshold_domain(logger, &qualified, Ordering::Less, value as f64)?;
+                    LayerRawCondition::LeInt(qualified.name, value)
+                }
+            }
+        }
+        DeclRawLayerTransitionCondition::Enum(query, order, value) => {
+            let qualified = first_pass.find_read_parameter(logger, &query.into(), ParameterType::INT_TYPE)?;
+            let index = resolve_enum_value(logger, &qualified, &value)? as i64;
+            match order {
+                DeclRawLayerTransitionOrdering::Equal => LayerRawCondition::EqInt(qualified.name, index),
+                DeclRawLayerTransitionOrdering::NotEqual => LayerRawCondition::NeqInt(qualified.name, index),
+                _ => {
+                    logger.log(Log::LayerInvalidCondition);
+                    return failure();
+                }
             }
         }
         DeclRawLayerTransitionCondition::Float(query, order, value) => {
             let qualified = first_pass.find_read_parameter(logger, &query.into(), ParameterType::FLOAT_TYPE)?;
             match order {
-                DeclRawLayerTransitionOrdering::Greater => LayerRawCondition::GtFloat(qualified.name, value),
-                DeclRawLayerTransitionOrdering::Lesser => LayerRawCondition::LeFloat(qualified.name, value),
+                DeclRawLayerTransitionOrdering::Greater => {
+                    check_threshold_domain(logger, &qualified, Ordering::Greater, value)?;
+                    LayerRawCondition::GtFloat(qualified.name, value)
+                }
+                DeclRawLayerTransitionOrdering::Lesser => {
+                    check_threshold_domain(logger, &qualified, Ordering::Less, value)?;
+                    LayerRawCondition::LeFloat(qualified.name, value)
+                }
                 _ => {
                     logger.log(Log::LayerInvalidCondition);
                     return failure();
@@ -5,7 +5,11 @@ use crate::{
             parameter::ParameterType,
         },
         log::Log,
-        transformer::{failure, success, Compiled, FirstPassData},
+        transformer::{
+            failure,
+            parameter::{check_float_domain, check_int_domain, resolve_enum_value},
+            success, Compiled, FirstPassData,
+        },
     },
     decl_v2::data::{
         driver::DeclParameterDrive,
@@ -85,8 +89,14 @@ fn compile_boolean(
         }
         DeclParameterDrive::SetInt { parameter, value } => {
             let qualified = first_pass.find_writable_parameter(&logger, &parameter.into(), ParameterType::INT_TYPE)?;
+            check_int_domain(&logger, &qualified, value)?;
             (qualified.name, ParameterType::Int(value as u8))
         }
+        DeclParameterDrive::SetEnum { parameter, value } => {
+            let qualified = first_pass.find_writable_parameter(&logger, &parameter.into(), ParameterType::INT_TYPE)?;
+            let index = resolve_enum_value(&logger, &qualified, &value)?;
+            (qualified.name, ParameterType::Int(index))
+        }
         DeclParameterDrive::SetBool { parameter, value } => {
             let qualified = first_pass.find_writable_parameter(&logger, &parameter.into(), ParameterType::BOOL_TYPE)?;
             (qualified.name, ParameterType::Bool(value.unwrap_or(true)))
@@ -94,7 +104,9 @@ fn compile_boolean(
         DeclParameterDrive::SetFloat { parameter, value } => {
             let qualified =
                 first_pass.find_writable_parameter(&logger, &parameter.into(), ParameterType::FLOAT_TYPE)?;
-            (qualified.name, ParameterType::Float(value.unwrap_or(1.0)))
+            let value = value.unwrap_or(1.0);
+            check_float_domain(&logger, &qualified, value)?;
+            (qualified.name, ParameterType::Float(value))
         }
         _ => {
             logger.log(Log::MenuInvalidDrive);
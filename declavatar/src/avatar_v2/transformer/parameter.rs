@@ -1,16 +1,24 @@
 use crate::{
     avatar_v2::{
-        data::parameter::{DeclaredParameter, Parameter, ParameterScope, ParameterType, ProvidedParameter},
+        data::{
+            contact::ContactReceiver,
+            parameter::{
+                DeclaredParameter, Parameter, ParameterDomain, ParameterScope, ParameterType, ProvidedParameter,
+                QualifiedParameter,
+            },
+        },
         log::Log,
         transformer::{failure, success, Compiled},
     },
     decl_v2::data::parameter::{
-        DeclParameter, DeclParameters, DeclPrimitiveParameter, DeclPrimitiveParameterScope, DeclPrimitiveParameterType,
-        DeclProvidedParameterKind,
+        DeclContactParameter, DeclEnumParameter, DeclParameter, DeclParameters, DeclPrimitiveParameter,
+        DeclPrimitiveParameterScope, DeclPrimitiveParameterType, DeclProvidedParameterKind,
     },
     log::Logger,
 };
 
+use std::cmp::Ordering;
+
 pub fn compile_parameters_blocks(
     logger: &Logger<Log>,
     parameters_blocks: Vec<DeclParameters>,
@@ -26,6 +34,12 @@ pub fn compile_parameters_blocks(
                     };
                     parameters.push(parameter);
                 }
+                DeclParameter::Enum(decl_enum) => {
+                    let Some(parameter) = compile_enum_parameter(&logger, decl_enum, &parameters) else {
+                        continue;
+                    };
+                    parameters.push(parameter);
+                }
                 DeclParameter::Provided(vrc_kinds) => {
                     let Some(vrc_parameters) = compile_vrc_parameters(vrc_kinds) else {
                         unreachable!("VRChat parameters must compile");
@@ -38,6 +52,12 @@ pub fn compile_parameters_blocks(
                     };
                     parameters.push(pb_parameter);
                 }
+                DeclParameter::Contact(decl_contact) => {
+                    let Some(parameter) = compile_contact_parameter(&logger, decl_contact, &parameters) else {
+                        continue;
+                    };
+                    parameters.push(parameter);
+                }
             }
         }
     }
@@ -56,17 +76,14 @@ fn compile_primitive_parameter(
         DeclPrimitiveParameterType::Float(dv) => (ParameterType::Float(dv.unwrap_or(0.0)), dv.is_some()),
         DeclPrimitiveParameterType::Bool(dv) => (ParameterType::Bool(dv.unwrap_or(false)), dv.is_some()),
     };
-    let scope = match (decl_parameter.scope, decl_parameter.save) {
-        (Some(DeclPrimitiveParameterScope::Internal), None | Some(false)) => ParameterScope::Internal,
-        (Some(DeclPrimitiveParameterScope::Local), None) => ParameterScope::Local(false),
-        (Some(DeclPrimitiveParameterScope::Local), Some(saved)) => ParameterScope::Local(saved),
-        (None | Some(DeclPrimitiveParameterScope::Synced), None) => ParameterScope::Synced(false),
-        (None | Some(DeclPrimitiveParameterScope::Synced), Some(saved)) => ParameterScope::Synced(saved),
-
-        (Some(DeclPrimitiveParameterScope::Internal), Some(true)) => {
-            logger.log(Log::InternalMustBeTransient(decl_parameter.name));
+    let scope = compile_parameter_scope(logger, &name, decl_parameter.scope, decl_parameter.save)?;
+    let domain = match decl_parameter.range {
+        (None, None) => ParameterDomain::Unbounded,
+        (Some(min), Some(max)) if min > max => {
+            logger.log(Log::ParameterInvalidRange(name));
             return failure();
         }
+        (min, max) => ParameterDomain::Range(min, max),
     };
 
     if declared.iter().any(|p| name == p.basename()) {
@@ -74,15 +91,164 @@ fn compile_primitive_parameter(
         return failure();
     }
 
+    let default_value = match value_type {
+        ParameterType::Int(dv) => dv as f64,
+        ParameterType::Float(dv) => dv,
+        ParameterType::Bool(_) => 0.0,
+    };
+    if explicit_default && !domain.contains(default_value) {
+        logger.log(Log::ParameterValueOutOfRange(name.clone(), default_value.to_string()));
+    }
+
     success(Parameter::Declared(DeclaredParameter {
         name,
         value_type,
         scope,
         unique: decl_parameter.unique.unwrap_or(false),
         explicit_default,
+        domain,
+    }))
+}
+
+fn compile_enum_parameter(
+    logger: &Logger<Log>,
+    decl_parameter: DeclEnumParameter,
+    declared: &[Parameter],
+) -> Compiled<Parameter> {
+    let name = decl_parameter.name;
+    let values = decl_parameter.values;
+    let has_duplicate = values.iter().enumerate().any(|(i, v)| values[..i].contains(v));
+    if values.is_empty() || values.len() > 256 || has_duplicate {
+        logger.log(Log::ParameterEnumInvalid(name));
+        return failure();
+    }
+
+    let domain = ParameterDomain::Enum(values);
+    let default_index = match &decl_parameter.default {
+        Some(default_name) => {
+            let Some(index) = domain.enum_index(default_name) else {
+                logger.log(Log::ParameterEnumValueNotFound(name, default_name.clone()));
+                return failure();
+            };
+            index as u8
+        }
+        None => 0,
+    };
+    let scope = compile_parameter_scope(logger, &name, decl_parameter.scope, decl_parameter.save)?;
+
+    if declared.iter().any(|p| name == p.basename()) {
+        logger.log(Log::IncompatibleParameterDeclaration(name));
+        return failure();
+    }
+
+    success(Parameter::Declared(DeclaredParameter {
+        name,
+        value_type: ParameterType::Int(default_index),
+        scope,
+        unique: decl_parameter.unique.unwrap_or(false),
+        explicit_default: decl_parameter.default.is_some(),
+        domain,
     }))
 }
 
+fn compile_contact_parameter(
+    logger: &Logger<Log>,
+    decl_parameter: DeclContactParameter,
+    declared: &[Parameter],
+) -> Compiled<Parameter> {
+    if declared.iter().any(|p| decl_parameter.name == p.basename()) {
+        logger.log(Log::IncompatibleParameterDeclaration(decl_parameter.name));
+        return failure();
+    }
+
+    success(Parameter::Provided(ProvidedParameter::Contact(ContactReceiver {
+        parameter: decl_parameter.name,
+        receiver_type: decl_parameter.kind.into(),
+        root_object: decl_parameter.root,
+        radius: decl_parameter.radius.unwrap_or(0.5),
+        collision_tags: decl_parameter.tags,
+        allow_self: decl_parameter.allow_self.unwrap_or(true),
+        allow_others: decl_parameter.allow_others.unwrap_or(true),
+        local_only: decl_parameter.local_only.unwrap_or(false),
+    })))
+}
+
+fn compile_parameter_scope(
+    logger: &Logger<Log>,
+    name: &str,
+    scope: Option<DeclPrimitiveParameterScope>,
+    save: Option<bool>,
+) -> Compiled<ParameterScope> {
+    let scope = match (scope, save) {
+        (Some(DeclPrimitiveParameterScope::Internal), None | Some(false)) => ParameterScope::Internal,
+        (Some(DeclPrimitiveParameterScope::Local), None) => ParameterScope::Local(false),
+        (Some(DeclPrimitiveParameterScope::Local), Some(saved)) => ParameterScope::Local(saved),
+        (None | Some(DeclPrimitiveParameterScope::Synced), None) => ParameterScope::Synced(false),
+        (None | Some(DeclPrimitiveParameterScope::Synced), Some(saved)) => ParameterScope::Synced(saved),
+
+        (Some(DeclPrimitiveParameterScope::Internal), Some(true)) => {
+            logger.log(Log::InternalMustBeTransient(name.to_string()));
+            return failure();
+        }
+    };
+    success(scope)
+}
+
+// resolves a named value of enum parameter into its index
+pub fn resolve_enum_value(logger: &Logger<Log>, qualified: &QualifiedParameter, value_name: &str) -> Compiled<u8> {
+    let Some(index) = qualified.domain.enum_index(value_name) else {
+        logger.log(Log::ParameterEnumValueNotFound(
+            qualified.name.clone(),
+            value_name.to_string(),
+        ));
+        return failure();
+    };
+    success(index as u8)
+}
+
+// values outside of enum are errors, while ones outside of declared range are only warned
+pub fn check_int_domain(logger: &Logger<Log>, qualified: &QualifiedParameter, value: i64) -> Compiled<()> {
+    if qualified.domain.contains(value as f64) {
+        return success(());
+    }
+    match qualified.domain {
+        ParameterDomain::Enum(_) => {
+            logger.log(Log::ParameterEnumOutOfRange(qualified.name.clone(), value));
+            failure()
+        }
+        _ => {
+            logger.log(Log::ParameterValueOutOfRange(qualified.name.clone(), value.to_string()));
+            success(())
+        }
+    }
+}
+
+pub fn check_float_domain(logger: &Logger<Log>, qualified: &QualifiedParameter, value: f64) -> Compiled<()> {
+    if !qualified.domain.contains(value) {
+        logger.log(Log::ParameterValueOutOfRange(qualified.name.clone(), value.to_string()));
+    }
+    success(())
+}
+
+// thresholds of ordered comparisons may lie outside of the domain, so only ones never satisfied are warned
+pub fn check_threshold_domain(
+    logger: &Logger<Log>,
+    qualified: &QualifiedParameter,
+    ordering: Ordering,
+    value: f64,
+) -> Compiled<()> {
+    let (min, max) = qualified.domain.bounds();
+    let never_satisfied = match ordering {
+        Ordering::Greater => max.is_some_and(|max| value >= max),
+        Ordering::Less => min.is_some_and(|min| value <= min),
+        Ordering::Equal => !qualified.domain.contains(value),
+    };
+    if never_satisfied {
+        logger.log(Log::ParameterValueOutOfRange(qualified.name.clone(), value.to_string()));
+    }
+    success(())
+}
+
 fn compile_vrc_parameters(kinds: Vec<DeclProvidedParameterKind>) -> Compiled<Vec<Parameter>> {
     let parameters = kinds
         .into_iter()
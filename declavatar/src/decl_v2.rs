@@ -1,12 +1,20 @@
 pub mod data;
 pub mod error;
+mod merge;
 mod sexpr;
 
-use crate::decl_v2::{data::avatar::DeclAvatar, error::DeclError, sexpr::load_avatar_sexpr};
+use crate::decl_v2::{
+    data::avatar::{DeclAvatar, DeclModule},
+    error::DeclError,
+    merge::DeclarationMerger,
+    sexpr::{load_avatar_sexpr, load_module_sexpr},
+};
 
 use std::{
     collections::{HashMap, HashSet},
+    fmt::{Debug, Formatter, Result as FmtResult},
     path::PathBuf,
+    sync::Arc,
 };
 
 use serde::{Deserialize, Serialize};
@@ -16,6 +24,9 @@ pub struct Arguments {
     library_paths: HashSet<PathBuf>,
     symbols: HashSet<String>,
     localizations: HashMap<String, String>,
+
+    #[serde(skip)]
+    module_resolver: Option<ModuleResolver>,
 }
 
 impl Arguments {
@@ -27,6 +38,7 @@ impl Arguments {
         self.library_paths.clear();
         self.symbols.clear();
         self.localizations.clear();
+        self.module_resolver = None;
     }
 
     pub fn add_library_path(&mut self, path: impl Into<PathBuf>) -> bool {
@@ -62,6 +74,43 @@ impl Arguments {
     pub fn localizations(&self) -> &HashMap<String, String> {
         &self.localizations
     }
+
+    // consulted before library paths
+    pub fn set_module_resolver(&mut self, resolver: Option<ModuleResolver>) {
+        self.module_resolver = resolver;
+    }
+
+    pub fn module_resolver(&self) -> Option<&ModuleResolver> {
+        self.module_resolver.as_ref()
+    }
+}
+
+type ResolverFn = dyn Fn(&str) -> Option<String> + Send + Sync;
+
+// resolves module name into its source text
+#[derive(Clone)]
+pub struct ModuleResolver(Arc<ResolverFn>);
+
+impl ModuleResolver {
+    pub fn new(resolver: impl Fn(&str) -> Option<String> + Send + Sync + 'static) -> ModuleResolver {
+        ModuleResolver(Arc::new(resolver))
+    }
+
+    pub fn resolve(&self, name: &str) -> Option<String> {
+        (self.0)(name)
+    }
+}
+
+impl Debug for ModuleResolver {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("ModuleResolver").finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for ModuleResolver {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
 }
 
 #[non_exhaustive]
@@ -77,3 +126,47 @@ pub fn compile_declaration(text: &str, format: DeclarationFormat, args: Argument
         _ => Err(DeclError::UnsupportedFormat),
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeclarationSource<'a> {
+    pub name: &'a str,
+    pub text: &'a str,
+    pub format: DeclarationFormat,
+}
+
+pub fn compile_declarations(
+    base: DeclarationSource,
+    modules: &[DeclarationSource],
+    args: Arguments,
+) -> Result<DeclAvatar, DeclError> {
+    let base_avatar = compile_declaration(base.text, base.format, args.clone())?;
+
+    let mut merger = DeclarationMerger::new(base.name, base_avatar);
+    let mut conflicting_sources = vec![];
+    for module_source in modules {
+        let module = compile_module(module_source.text, module_source.format, args.clone())
+            .map_err(|e| DeclError::ModuleFailed(module_source.name.to_string(), e.to_string()))?;
+        // conflicting modules are skipped so that the following ones are still checked
+        if let Err(conflicts) = merger.merge(module_source.name, module) {
+            conflicting_sources.push((module_source.name, conflicts.join(", ")));
+        }
+    }
+
+    if !conflicting_sources.is_empty() {
+        let names = conflicting_sources.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+        let conflicts = conflicting_sources
+            .iter()
+            .map(|(name, conflicts)| format!("{name}: {conflicts}"))
+            .collect::<Vec<_>>();
+        return Err(DeclError::ModuleConflict(names.join(", "), conflicts.join("; ")));
+    }
+
+    Ok(merger.finish())
+}
+
+fn compile_module(text: &str, format: DeclarationFormat, args: Arguments) -> Result<DeclModule, DeclError> {
+    match format {
+        DeclarationFormat::Sexpr => load_module_sexpr(text, args),
+        _ => Err(DeclError::UnsupportedFormat),
+    }
+}
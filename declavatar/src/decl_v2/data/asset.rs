@@ -1,14 +1,16 @@
 use crate::static_type_name_impl;
 
 use ketos::{ForeignValue, FromValue, FromValueRef, IntoValue};
+use serde::Serialize;
 
-#[derive(Debug, Clone, PartialEq, Eq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
 pub struct DeclAssets {
     pub assets: Vec<DeclAsset>,
 }
 static_type_name_impl!(DeclAssets);
 
-#[derive(Debug, Clone, PartialEq, Eq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[serde(tag = "type", content = "content")]
 pub enum DeclAsset {
     Material(String),
     Animation(String),
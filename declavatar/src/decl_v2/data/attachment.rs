@@ -1,31 +1,38 @@
-use crate::static_type_name_impl;
+use crate::{decl_v2::data::parameter::DeclParameterReference, static_type_name_impl};
+
+use std::collections::BTreeMap;
 
 use ketos::{ForeignValue, FromValue, FromValueRef, IntoValue};
+use serde::Serialize;
 
-#[derive(Debug, Clone, PartialEq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
 pub struct DeclAttachments {
     pub attachments: Vec<DeclAttachment>,
 }
 static_type_name_impl!(DeclAttachments);
 
-#[derive(Debug, Clone, PartialEq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
 pub struct DeclAttachment {
     pub name: String,
+    pub version: Option<u32>,
     pub properties: Vec<DeclAttachmentProperty>,
 }
 static_type_name_impl!(DeclAttachment);
 
-#[derive(Debug, Clone, PartialEq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
 pub struct DeclAttachmentProperty {
     pub name: String,
     pub parameters: Vec<DeclAttachmentValue>,
+    pub keywords: BTreeMap<String, DeclAttachmentValue>,
 }
 static_type_name_impl!(DeclAttachmentProperty);
 
-#[derive(Debug, Clone, PartialEq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[serde(tag = "type", content = "content")]
 pub enum DeclAttachmentValue {
     Null,
     UntypedList(Vec<DeclAttachmentValue>),
+    Map(Vec<(DeclAttachmentValue, DeclAttachmentValue)>),
     Boolean(bool),
     Integer(i64),
     Float(f64),
@@ -34,5 +41,6 @@ pub enum DeclAttachmentValue {
     GameObject(String),
     Material(String),
     AnimationClip(String),
+    Parameter(DeclParameterReference),
 }
 static_type_name_impl!(DeclAttachmentValue);
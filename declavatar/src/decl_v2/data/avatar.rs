@@ -7,8 +7,9 @@ use crate::{
 };
 
 use ketos::{ForeignValue, FromValue, FromValueRef, IntoValue};
+use serde::Serialize;
 
-#[derive(Debug, Clone, PartialEq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
 pub struct DeclAvatar {
     pub name: String,
     pub exports_blocks: Vec<DeclExports>,
@@ -19,3 +20,29 @@ pub struct DeclAvatar {
     pub attachment_blocks: Vec<DeclAttachments>,
 }
 static_type_name_impl!(DeclAvatar);
+
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
+pub struct DeclModule {
+    pub name: String,
+    pub exports_blocks: Vec<DeclExports>,
+    pub parameters_blocks: Vec<DeclParameters>,
+    pub assets_blocks: Vec<DeclAssets>,
+    pub fx_controllers: Vec<DeclFxController>,
+    pub menu_blocks: Vec<DeclSubMenu>,
+    pub attachment_blocks: Vec<DeclAttachments>,
+}
+static_type_name_impl!(DeclModule);
+
+impl From<DeclAvatar> for DeclModule {
+    fn from(avatar: DeclAvatar) -> DeclModule {
+        DeclModule {
+            name: avatar.name,
+            exports_blocks: avatar.exports_blocks,
+            parameters_blocks: avatar.parameters_blocks,
+            assets_blocks: avatar.assets_blocks,
+            fx_controllers: avatar.fx_controllers,
+            menu_blocks: avatar.menu_blocks,
+            attachment_blocks: avatar.attachment_blocks,
+        }
+    }
+}
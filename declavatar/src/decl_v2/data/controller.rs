@@ -1,8 +1,9 @@
 use crate::{decl_v2::data::layer::DeclControllerLayer, static_type_name_impl};
 
 use ketos::{ForeignValue, FromValue, FromValueRef, IntoValue};
+use serde::Serialize;
 
-#[derive(Debug, Clone, PartialEq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
 pub struct DeclFxController {
     pub layers: Vec<DeclControllerLayer>,
 }
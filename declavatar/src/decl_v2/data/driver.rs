@@ -1,8 +1,10 @@
 use crate::{decl_v2::data::parameter::DeclParameterReference, static_type_name_impl};
 
 use ketos::{ForeignValue, FromValue, FromValueRef, IntoValue};
+use serde::Serialize;
 
-#[derive(Debug, Clone, PartialEq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[serde(tag = "type", content = "content")]
 pub enum DeclParameterDrive {
     Group(DeclDriveGroup),
     Switch(DeclDriveSwitch),
@@ -11,6 +13,10 @@ pub enum DeclParameterDrive {
         parameter: DeclParameterReference,
         value: i64,
     },
+    SetEnum {
+        parameter: DeclParameterReference,
+        value: String,
+    },
     SetBool {
         parameter: DeclParameterReference,
         value: Option<bool>,
@@ -47,35 +53,35 @@ pub enum DeclParameterDrive {
 }
 static_type_name_impl!(DeclParameterDrive);
 
-#[derive(Debug, Clone, PartialEq, Eq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
 pub struct DeclDriveGroup {
     pub group: String,
     pub option: String,
 }
 static_type_name_impl!(DeclDriveGroup);
 
-#[derive(Debug, Clone, PartialEq, Eq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
 pub struct DeclDriveSwitch {
     pub switch: String,
     pub value: Option<bool>,
 }
 static_type_name_impl!(DeclDriveSwitch);
 
-#[derive(Debug, Clone, PartialEq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
 pub struct DeclDrivePuppet {
     pub puppet: String,
     pub value: Option<f64>,
 }
 static_type_name_impl!(DeclDrivePuppet);
 
-#[derive(Debug, Clone, PartialEq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
 pub struct DeclTrackingControl {
     pub animation_desired: bool,
     pub targets: Vec<DeclTrackingTarget>,
 }
 static_type_name_impl!(DeclTrackingControl);
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum DeclTrackingTarget {
     Head,
     Hip,
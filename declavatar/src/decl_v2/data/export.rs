@@ -1,14 +1,16 @@
 use crate::{decl_v2::data::parameter::DeclParameterReference, static_type_name_impl};
 
 use ketos::{ForeignValue, FromValue, FromValueRef, IntoValue};
+use serde::Serialize;
 
-#[derive(Debug, Clone, PartialEq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
 pub struct DeclExports {
     pub exports: Vec<DeclExport>,
 }
 static_type_name_impl!(DeclExports);
 
-#[derive(Debug, Clone, PartialEq, Eq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[serde(tag = "type", content = "content")]
 pub enum DeclExport {
     Gate(String),
     Guard(String, DeclParameterReference),
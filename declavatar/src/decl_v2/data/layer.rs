@@ -7,8 +7,10 @@ use crate::{
 };
 
 use ketos::{ForeignValue, FromValue, FromValueRef, IntoValue};
+use serde::Serialize;
 
-#[derive(Debug, Clone, PartialEq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[serde(tag = "type", content = "content")]
 pub enum DeclControllerLayer {
     Group(DeclGroupLayer),
     Switch(DeclSwitchLayer),
@@ -17,7 +19,7 @@ pub enum DeclControllerLayer {
 }
 static_type_name_impl!(DeclControllerLayer);
 
-#[derive(Debug, Clone, PartialEq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
 pub struct DeclGroupLayer {
     pub name: String,
     pub driven_by: DeclParameterReference,
@@ -25,17 +27,18 @@ pub struct DeclGroupLayer {
     pub copy_mode: Option<DeclGroupCopyMode>,
     pub default: Option<DeclGroupOption>,
     pub options: Vec<DeclGroupOption>,
+    pub allow_overlap: Vec<String>,
 }
 static_type_name_impl!(DeclGroupLayer);
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum DeclGroupCopyMode {
     ToDefaultZeroed,
     ToOption,
     MutualZeroed,
 }
 
-#[derive(Debug, Clone, PartialEq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
 pub struct DeclGroupOption {
     pub kind: DeclGroupOptionKind,
     pub animation_asset: Option<String>,
@@ -43,14 +46,16 @@ pub struct DeclGroupOption {
 }
 static_type_name_impl!(DeclGroupOption);
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", content = "content")]
 pub enum DeclGroupOptionKind {
     Boolean(bool),
     Selection(Option<String>, Option<usize>),
     Keyframe(f64),
 }
 
-#[derive(Debug, Clone, PartialEq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[serde(tag = "type", content = "content")]
 pub enum DeclGroupOptionTarget {
     Shape(DeclGroupShapeTarget),
     Object(DeclGroupObjectTarget),
@@ -61,7 +66,7 @@ pub enum DeclGroupOptionTarget {
 }
 static_type_name_impl!(DeclGroupOptionTarget);
 
-#[derive(Debug, Clone, PartialEq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
 pub struct DeclGroupShapeTarget {
     pub shape: String,
     pub value: Option<f64>,
@@ -69,14 +74,14 @@ pub struct DeclGroupShapeTarget {
 }
 static_type_name_impl!(DeclGroupShapeTarget);
 
-#[derive(Debug, Clone, PartialEq, Eq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
 pub struct DeclGroupObjectTarget {
     pub object: String,
     pub value: Option<bool>,
 }
 static_type_name_impl!(DeclGroupObjectTarget);
 
-#[derive(Debug, Clone, PartialEq, Eq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
 pub struct DeclGroupMaterialTarget {
     pub index: usize,
     pub value: String,
@@ -84,7 +89,7 @@ pub struct DeclGroupMaterialTarget {
 }
 static_type_name_impl!(DeclGroupMaterialTarget);
 
-#[derive(Debug, Clone, PartialEq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
 pub struct DeclGroupMaterialPropertyTarget {
     pub property: String,
     pub value: DeclMaterialValue,
@@ -92,7 +97,8 @@ pub struct DeclGroupMaterialPropertyTarget {
 }
 static_type_name_impl!(DeclGroupMaterialPropertyTarget);
 
-#[derive(Debug, Clone, PartialEq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[serde(tag = "type", content = "content")]
 pub enum DeclMaterialValue {
     Float(f64),
     Color([f64; 4]),
@@ -101,7 +107,7 @@ pub enum DeclMaterialValue {
 }
 static_type_name_impl!(DeclMaterialValue);
 
-#[derive(Debug, Clone, PartialEq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
 pub struct DeclSwitchLayer {
     pub name: String,
     pub driven_by: Option<DeclParameterReference>,
@@ -109,28 +115,31 @@ pub struct DeclSwitchLayer {
     pub default_mesh: Option<String>,
     pub disabled: DeclGroupOption,
     pub enabled: DeclGroupOption,
+    pub allow_overlap: Vec<String>,
 }
 static_type_name_impl!(DeclSwitchLayer);
 
-#[derive(Debug, Clone, PartialEq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
 pub struct DeclPuppetLayer {
     pub name: String,
     pub driven_by: DeclParameterReference,
     pub default_mesh: Option<String>,
     pub animation_asset: Option<String>,
     pub keyframes: Vec<DeclGroupOption>,
+    pub allow_overlap: Vec<String>,
 }
 static_type_name_impl!(DeclPuppetLayer);
 
-#[derive(Debug, Clone, PartialEq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
 pub struct DeclRawLayer {
     pub name: String,
     pub default: Option<String>,
     pub states: Vec<DeclRawLayerState>,
+    pub allow_overlap: Vec<String>,
 }
 static_type_name_impl!(DeclRawLayer);
 
-#[derive(Debug, Clone, PartialEq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
 pub struct DeclRawLayerState {
     pub name: String,
     pub kind: DeclRawLayerAnimationKind,
@@ -138,7 +147,8 @@ pub struct DeclRawLayerState {
 }
 static_type_name_impl!(DeclRawLayerState);
 
-#[derive(Debug, Clone, PartialEq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[serde(tag = "type", content = "content")]
 pub enum DeclRawLayerAnimationKind {
     Clip {
         animation: DeclRawLayerAnimation,
@@ -152,7 +162,8 @@ pub enum DeclRawLayerAnimationKind {
 }
 static_type_name_impl!(DeclRawLayerAnimation);
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", content = "content")]
 pub enum DeclRawLayerBlendTreeType {
     Linear(DeclParameterReference),
     Simple2D(DeclParameterReference, DeclParameterReference),
@@ -160,26 +171,27 @@ pub enum DeclRawLayerBlendTreeType {
     Cartesian2D(DeclParameterReference, DeclParameterReference),
 }
 
-#[derive(Debug, Clone, PartialEq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
 pub struct DeclRawLayerBlendTreeField {
     pub animation: DeclRawLayerAnimation,
     pub values: [f64; 2],
 }
 static_type_name_impl!(DeclRawLayerBlendTreeField);
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", content = "content")]
 pub enum DeclRawLayerAnimation {
     Inline(DeclLayerInlineAnimation),
     External(String),
 }
 
-#[derive(Debug, Clone, PartialEq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
 pub struct DeclLayerInlineAnimation {
     pub targets: Vec<DeclGroupOptionTarget>,
 }
 static_type_name_impl!(DeclLayerInlineAnimation);
 
-#[derive(Debug, Clone, PartialEq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
 pub struct DeclRawLayerTransition {
     pub target: String,
     pub duration: Option<f64>,
@@ -187,16 +199,18 @@ pub struct DeclRawLayerTransition {
 }
 static_type_name_impl!(DeclRawLayerTransition);
 
-#[derive(Debug, Clone, PartialEq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[serde(tag = "type", content = "content")]
 pub enum DeclRawLayerTransitionCondition {
     Zero(DeclParameterReference, bool),
     Bool(DeclParameterReference, bool),
     Int(DeclParameterReference, DeclRawLayerTransitionOrdering, i64),
     Float(DeclParameterReference, DeclRawLayerTransitionOrdering, f64),
+    Enum(DeclParameterReference, DeclRawLayerTransitionOrdering, String),
 }
 static_type_name_impl!(DeclRawLayerTransitionCondition);
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum DeclRawLayerTransitionOrdering {
     Equal,
     NotEqual,
@@ -204,6 +218,18 @@ pub enum DeclRawLayerTransitionOrdering {
     Lesser,
 }
 
+impl DeclControllerLayer {
+    // driving keys this layer is allowed to share with other layers
+    pub fn allow_overlap(&self) -> &[String] {
+        match self {
+            DeclControllerLayer::Group(group) => &group.allow_overlap,
+            DeclControllerLayer::Switch(switch) => &switch.allow_overlap,
+            DeclControllerLayer::Puppet(puppet) => &puppet.allow_overlap,
+            DeclControllerLayer::Raw(raw) => &raw.allow_overlap,
+        }
+    }
+}
+
 impl DeclGroupOptionKind {
     pub fn as_all_selection(&self) -> Option<(Option<String>, Option<usize>)> {
         let DeclGroupOptionKind::Selection(name, value) = self else {
@@ -7,8 +7,10 @@ use crate::{
 };
 
 use ketos::{ForeignValue, FromValue, FromValueRef, IntoValue};
+use serde::Serialize;
 
-#[derive(Debug, Clone, PartialEq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[serde(tag = "type", content = "content")]
 pub enum DeclMenuElement {
     SubMenu(DeclSubMenu),
     Boolean(DeclBooleanControl),
@@ -16,34 +18,35 @@ pub enum DeclMenuElement {
 }
 static_type_name_impl!(DeclMenuElement);
 
-#[derive(Debug, Clone, PartialEq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
 pub struct DeclSubMenu {
     pub name: String,
     pub elements: Vec<DeclMenuElement>,
 }
 static_type_name_impl!(DeclSubMenu);
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct DeclBooleanControl {
     pub name: String,
     pub hold: bool,
     pub parameter_drive: DeclParameterDrive,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct DeclPuppetControl {
     pub name: String,
     pub puppet_type: Box<DeclPuppetType>,
 }
 
-#[derive(Debug, Clone, PartialEq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
 pub struct DeclPuppetAxis {
     pub target: DeclPuppetTarget,
     pub label_positive: Option<String>,
     pub label_negative: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", content = "content")]
 pub enum DeclPuppetType {
     Radial(DeclPuppetAxis),
     TwoAxis {
@@ -58,7 +61,8 @@ pub enum DeclPuppetType {
     },
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", content = "content")]
 pub enum DeclPuppetTarget {
     Puppet(DeclDrivePuppet),
     Parameter(DeclParameterReference),
@@ -3,60 +3,114 @@ use crate::static_type_name_impl;
 use std::str::FromStr;
 
 use ketos::{ForeignValue, FromValue, FromValueRef, IntoValue};
+use serde::Serialize;
 
-#[derive(Debug, Clone, PartialEq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
 pub struct DeclParameters {
     pub parameters: Vec<DeclParameter>,
 }
 static_type_name_impl!(DeclParameters);
 
-#[derive(Debug, Clone, PartialEq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[serde(tag = "type", content = "content")]
 pub enum DeclParameter {
     Primitive(DeclPrimitiveParameter),
+    Enum(DeclEnumParameter),
     PhysBone(DeclPhysBoneParameter),
+    Contact(DeclContactParameter),
     Provided(Vec<DeclProvidedParameterKind>),
 }
 static_type_name_impl!(DeclParameter);
 
-#[derive(Debug, Clone, PartialEq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
 pub struct DeclPrimitiveParameter {
     pub ty: DeclPrimitiveParameterType,
     pub name: String,
     pub scope: Option<DeclPrimitiveParameterScope>,
     pub save: Option<bool>,
     pub unique: Option<bool>,
+    pub range: (Option<f64>, Option<f64>),
 }
 static_type_name_impl!(DeclPrimitiveParameter);
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
+pub struct DeclEnumParameter {
+    pub name: String,
+    pub values: Vec<String>,
+    pub default: Option<String>,
+    pub scope: Option<DeclPrimitiveParameterScope>,
+    pub save: Option<bool>,
+    pub unique: Option<bool>,
+}
+static_type_name_impl!(DeclEnumParameter);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(tag = "type", content = "content")]
 pub enum DeclPrimitiveParameterType {
     Int(Option<u8>),
     Float(Option<f64>),
     Bool(Option<bool>),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum DeclPrimitiveParameterScope {
     Internal,
     Local,
     Synced,
 }
 
-#[derive(Debug, Clone, PartialEq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
 pub struct DeclPhysBoneParameter {
     pub prefix: String,
 }
 static_type_name_impl!(DeclPhysBoneParameter);
 
-#[derive(Debug, Clone, PartialEq, Eq, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[derive(Debug, Clone, PartialEq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
+pub struct DeclContactParameter {
+    pub name: String,
+    pub kind: DeclContactReceiverKind,
+    pub root: Option<String>,
+    pub radius: Option<f64>,
+    pub tags: Vec<String>,
+    pub allow_self: Option<bool>,
+    pub allow_others: Option<bool>,
+    pub local_only: Option<bool>,
+}
+static_type_name_impl!(DeclContactParameter);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum DeclContactReceiverKind {
+    Constant,
+    OnEnter,
+    Proximity,
+}
+
+impl FromStr for DeclContactReceiverKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let kind = match s {
+            "constant" => DeclContactReceiverKind::Constant,
+            "on-enter" => DeclContactReceiverKind::OnEnter,
+            "proximity" => DeclContactReceiverKind::Proximity,
+            _ => return Err(s.to_string()),
+        };
+        Ok(kind)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ForeignValue, FromValue, FromValueRef, IntoValue)]
+#[serde(tag = "type", content = "content")]
 pub enum DeclParameterReference {
     Primitive(String),
+    Global(String),
     PhysBone(String, DeclPhysBoneParameterKind),
+    Contact(String),
     Provided(DeclProvidedParameterKind),
 }
 static_type_name_impl!(DeclParameterReference);
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum DeclPhysBoneParameterKind {
     IsGrabbed,
     IsPosed,
@@ -81,7 +135,7 @@ impl FromStr for DeclPhysBoneParameterKind {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum DeclProvidedParameterKind {
     IsLocal,
     Viseme,
@@ -11,7 +11,23 @@ pub enum DeclError {
     #[error("internal error: {0}")]
     InternalError(String),
 
+    #[log_error("decl.parse_error")]
+    #[error("failed to parse declaration: {0}")]
+    ParseError(String),
+
+    #[log_error("decl.module_not_found")]
+    #[error("module {0} not found")]
+    ModuleNotFound(String),
+
     #[log_error("decl.not_returned")]
     #[error("cannot fetch declaration object ({0:?})")]
     DelclarationNotReturned(String),
+
+    #[log_error("decl.module_failed")]
+    #[error("failed to load module {0}: {1}")]
+    ModuleFailed(String, String),
+
+    #[log_error("decl.module_conflict")]
+    #[error("modules {0} conflict with preceding declarations: {1}")]
+    ModuleConflict(String, String),
 }
@@ -0,0 +1,167 @@
+use crate::decl_v2::data::{
+    asset::DeclAsset,
+    avatar::{DeclAvatar, DeclModule},
+    export::DeclExport,
+    layer::DeclControllerLayer,
+    parameter::DeclParameter,
+};
+
+use std::{collections::HashMap, mem::discriminant};
+
+pub struct DeclarationMerger {
+    avatar: DeclAvatar,
+    parameters: HashMap<String, (DeclParameter, String)>,
+    layers: HashMap<String, String>,
+    gates: HashMap<String, String>,
+    assets: HashMap<String, (DeclAsset, String)>,
+}
+
+impl DeclarationMerger {
+    pub fn new(source_name: &str, avatar: DeclAvatar) -> DeclarationMerger {
+        let mut merger = DeclarationMerger {
+            avatar: DeclAvatar {
+                name: avatar.name.clone(),
+                exports_blocks: vec![],
+                parameters_blocks: vec![],
+                assets_blocks: vec![],
+                fx_controllers: vec![],
+                menu_blocks: vec![],
+                attachment_blocks: vec![],
+            },
+            parameters: HashMap::new(),
+            layers: HashMap::new(),
+            gates: HashMap::new(),
+            assets: HashMap::new(),
+        };
+
+        merger.append(source_name, avatar.into());
+        merger
+    }
+
+    // named parameters identical to already declared ones are shared instead of conflicting
+    pub fn merge(&mut self, source_name: &str, mut module: DeclModule) -> Result<(), Vec<String>> {
+        let mut conflicts = vec![];
+
+        for parameters_block in &mut module.parameters_blocks {
+            parameters_block.parameters.retain(|parameter| {
+                let Some(name) = parameter_name(parameter) else {
+                    return true;
+                };
+                match self.parameters.get(name) {
+                    Some((declared, _)) if declared == parameter => false,
+                    Some((_, declared_source)) => {
+                        conflicts.push(format!("parameter '{name}' (declared by {declared_source})"));
+                        true
+                    }
+                    None => true,
+                }
+            });
+        }
+        for fx_controller in &module.fx_controllers {
+            for layer in &fx_controller.layers {
+                let name = layer_name(layer);
+                if let Some(declared_source) = self.layers.get(name) {
+                    conflicts.push(format!("layer '{name}' (declared by {declared_source})"));
+                }
+            }
+        }
+        for exports_block in &module.exports_blocks {
+            for export in &exports_block.exports {
+                let DeclExport::Gate(name) = export else {
+                    continue;
+                };
+                if let Some(declared_source) = self.gates.get(name) {
+                    conflicts.push(format!("gate '{name}' (declared by {declared_source})"));
+                }
+            }
+        }
+        for assets_block in &module.assets_blocks {
+            for asset in &assets_block.assets {
+                let key = asset_key(asset);
+                match self.assets.get(key) {
+                    Some((declared, declared_source)) if discriminant(declared) != discriminant(asset) => {
+                        conflicts.push(format!("asset '{key}' (declared by {declared_source})"));
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        if !conflicts.is_empty() {
+            return Err(conflicts);
+        }
+        self.append(source_name, module);
+        Ok(())
+    }
+
+    pub fn finish(self) -> DeclAvatar {
+        self.avatar
+    }
+
+    // declarations are recorded with the module name and its source for conflict messages
+    fn append(&mut self, source_name: &str, module: DeclModule) {
+        let origin = format!("'{}' in {source_name}", module.name);
+        for parameters_block in &module.parameters_blocks {
+            for parameter in &parameters_block.parameters {
+                if let Some(name) = parameter_name(parameter) {
+                    self.parameters
+                        .entry(name.to_string())
+                        .or_insert_with(|| (parameter.clone(), origin.clone()));
+                }
+            }
+        }
+        for fx_controller in &module.fx_controllers {
+            for layer in &fx_controller.layers {
+                self.layers
+                    .entry(layer_name(layer).to_string())
+                    .or_insert_with(|| origin.clone());
+            }
+        }
+        for exports_block in &module.exports_blocks {
+            for export in &exports_block.exports {
+                if let DeclExport::Gate(name) = export {
+                    self.gates.entry(name.clone()).or_insert_with(|| origin.clone());
+                }
+            }
+        }
+        for assets_block in &module.assets_blocks {
+            for asset in &assets_block.assets {
+                self.assets
+                    .entry(asset_key(asset).to_string())
+                    .or_insert_with(|| (asset.clone(), origin.clone()));
+            }
+        }
+
+        self.avatar.exports_blocks.extend(module.exports_blocks);
+        self.avatar.parameters_blocks.extend(module.parameters_blocks);
+        self.avatar.assets_blocks.extend(module.assets_blocks);
+        self.avatar.fx_controllers.extend(module.fx_controllers);
+        self.avatar.menu_blocks.extend(module.menu_blocks);
+        self.avatar.attachment_blocks.extend(module.attachment_blocks);
+    }
+}
+
+fn parameter_name(parameter: &DeclParameter) -> Option<&str> {
+    match parameter {
+        DeclParameter::Primitive(p) => Some(&p.name),
+        DeclParameter::Enum(p) => Some(&p.name),
+        DeclParameter::Contact(p) => Some(&p.name),
+        DeclParameter::Provided(_) | DeclParameter::PhysBone(_) => None,
+    }
+}
+
+fn layer_name(layer: &DeclControllerLayer) -> &str {
+    match layer {
+        DeclControllerLayer::Group(l) => &l.name,
+        DeclControllerLayer::Switch(l) => &l.name,
+        DeclControllerLayer::Puppet(l) => &l.name,
+        DeclControllerLayer::Raw(l) => &l.name,
+    }
+}
+
+fn asset_key(asset: &DeclAsset) -> &str {
+    match asset {
+        DeclAsset::Material(key) => key,
+        DeclAsset::Animation(key) => key,
+    }
+}
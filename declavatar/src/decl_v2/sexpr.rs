@@ -5,23 +5,48 @@ mod dain;
 mod error;
 
 use crate::decl_v2::{
-    data::{avatar::DeclAvatar, StaticTypeName},
+    data::{
+        avatar::{DeclAvatar, DeclModule},
+        StaticTypeName,
+    },
     error::DeclError,
     sexpr::{
         argument::SeparateArguments,
         error::{DeclSexprError, KetosResult},
     },
-    Arguments,
+    Arguments, ModuleResolver,
 };
 
 use std::{any::Any, rc::Rc};
 
 use ketos::{
     Arity, BuiltinModuleLoader, CompileError, Context, Error, FileModuleLoader, FromValueRef, Interpreter, Module,
-    ModuleLoader, Name, NameStore, Scope, Value,
+    ModuleBuilder, ModuleLoader, Name, NameStore, Scope, Value,
 };
 
 pub fn load_avatar_sexpr(text: &str, args: Arguments) -> Result<DeclAvatar, DeclError> {
+    let result = evaluate_sexpr(text, args)?;
+    match result.downcast_foreign_ref::<&DeclAvatar>() {
+        Ok(avatar) => Ok(avatar.clone()),
+        Err(e) => {
+            let error_text = e.to_string();
+            Err(DeclError::DelclarationNotReturned(error_text))
+        }
+    }
+}
+
+pub fn load_module_sexpr(text: &str, args: Arguments) -> Result<DeclModule, DeclError> {
+    let result = evaluate_sexpr(text, args)?;
+    match result.downcast_foreign_ref::<&DeclModule>() {
+        Ok(module) => Ok(module.clone()),
+        Err(e) => {
+            let error_text = e.to_string();
+            Err(DeclError::DelclarationNotReturned(error_text))
+        }
+    }
+}
+
+fn evaluate_sexpr(text: &str, args: Arguments) -> Result<Value, DeclError> {
     let file_loader = {
         let mut l = FileModuleLoader::with_search_paths(args.library_paths().cloned().collect());
         l.set_read_bytecode(false);
@@ -29,23 +54,27 @@ pub fn load_avatar_sexpr(text: &str, args: Arguments) -> Result<DeclAvatar, Decl
         l
     };
     let builtin_loader = BuiltinModuleLoader;
+    let resolver_loader = ResolverModuleLoader(args.module_resolver().cloned());
     let da_loader = DeclavatarModuleLoader(Rc::new(args));
 
-    let loader = Box::new(da_loader.chain(builtin_loader).chain(file_loader));
+    let loader = Box::new(
+        da_loader
+            .chain(builtin_loader)
+            .chain(resolver_loader)
+            .chain(file_loader),
+    );
     let interpreter = Interpreter::with_loader(loader);
 
-    let result = match interpreter.run_code(text, None) {
-        Ok(value) => value,
+    match interpreter.run_code(text, None) {
+        Ok(value) => Ok(value),
+        Err(Error::ParseError(perr)) => Err(DeclError::ParseError(perr.to_string())),
+        Err(Error::CompileError(CompileError::ModuleError(name))) => {
+            let module_name = interpreter.scope().with_name(name, |n| n.to_string());
+            Err(DeclError::ModuleNotFound(module_name))
+        }
         Err(kerr) => {
             let error_text = kerr.to_string();
-            return Err(DeclError::InternalError(error_text));
-        }
-    };
-    match result.downcast_foreign_ref::<&DeclAvatar>() {
-        Ok(avatar) => Ok(avatar.clone()),
-        Err(e) => {
-            let error_text = e.to_string();
-            Err(DeclError::DelclarationNotReturned(error_text))
+            Err(DeclError::InternalError(error_text))
         }
     }
 }
@@ -95,6 +124,24 @@ impl ModuleLoader for DeclavatarModuleLoader {
     }
 }
 
+// loads module source from the resolver registered in Arguments
+#[derive(Debug)]
+pub struct ResolverModuleLoader(Option<ModuleResolver>);
+
+impl ModuleLoader for ResolverModuleLoader {
+    fn load_module(&self, name: Name, ctx: Context) -> KetosResult<Module> {
+        let module_name = ctx.scope().with_name(name, |n| n.to_string());
+        let Some(source) = self.0.as_ref().and_then(|r| r.resolve(&module_name)) else {
+            return Err(From::from(CompileError::ModuleError(name)));
+        };
+
+        // evaluated in the scope prepared for this module, like FileModuleLoader does
+        let interpreter = Interpreter::with_context(ctx.clone());
+        interpreter.run_code(&source, Some(module_name.clone()))?;
+        Ok(ModuleBuilder::new(&module_name, ctx.scope().clone()).finish())
+    }
+}
+
 fn register_function<F: Any + for<'a> Fn(&'a NameStore, Name, SeparateArguments<'a>) -> KetosResult<Value>>(
     scope: &Scope,
     name: &'static str,
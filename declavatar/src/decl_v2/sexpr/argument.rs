@@ -41,6 +41,10 @@ impl<'a> SeparateArguments<'a> {
         Ok(value)
     }
 
+    pub fn kwargs(&'a self) -> impl Iterator<Item = (&'a str, &'a Value)> + 'a {
+        self.kwargs.iter().map(|(k, v)| (*k, *v))
+    }
+
     pub fn try_exact_arg<T: FromValueRef<'a>>(&'a self, index: usize) -> KetosResult<Option<T>> {
         let Some(raw_value) = self.args.get(index) else {
             return Ok(None);
@@ -7,6 +7,7 @@ pub(super) mod export;
 pub(super) mod layer_basic;
 pub(super) mod layer_raw;
 pub(super) mod menu;
+pub(super) mod namespace;
 pub(super) mod parameter;
 
 use crate::decl_v2::Arguments;
@@ -27,6 +28,7 @@ pub fn define_da_module(scope: Scope, preprocess: Rc<Arguments>) -> Module {
     layer_basic::register_layer_basic_function(&scope);
     layer_raw::register_layer_raw_function(&scope);
     driver::register_driver_function(&scope);
+    namespace::register_namespace_function(&scope);
     arguments::register_arguments_function(&scope, preprocess);
 
     ModuleBuilder::new(MODULE_NAME_DA, scope.clone()).finish()
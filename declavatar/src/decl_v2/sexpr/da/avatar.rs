@@ -1,7 +1,13 @@
 use crate::decl_v2::{
     data::{
-        asset::DeclAssets, attachment::DeclAttachments, avatar::DeclAvatar, controller::DeclFxController,
-        export::DeclExports, menu::DeclSubMenu, parameter::DeclParameters, StaticTypeName,
+        asset::DeclAssets,
+        attachment::DeclAttachments,
+        avatar::{DeclAvatar, DeclModule},
+        controller::DeclFxController,
+        export::DeclExports,
+        menu::DeclSubMenu,
+        parameter::DeclParameters,
+        StaticTypeName,
     },
     sexpr::{
         argument::SeparateArguments,
@@ -14,11 +20,22 @@ use ketos::{Arity, Error, Name, NameStore, Scope, Value};
 
 pub fn register_avatar_function(scope: &Scope) {
     register_function(scope, "avatar", declare_avatar, Arity::Min(1), Some(&[]));
+    register_function(scope, "module", declare_module, Arity::Min(1), Some(&[]));
 }
 
 fn declare_avatar(_name_store: &NameStore, function_name: Name, args: SeparateArguments) -> KetosResult<Value> {
     let name: &str = args.exact_arg(function_name, 0)?;
+    let avatar = collect_blocks(name, function_name, &args)?;
+    Ok(avatar.into())
+}
+
+fn declare_module(_name_store: &NameStore, function_name: Name, args: SeparateArguments) -> KetosResult<Value> {
+    let name: &str = args.exact_arg(function_name, 0)?;
+    let module = DeclModule::from(collect_blocks(name, function_name, &args)?);
+    Ok(module.into())
+}
 
+fn collect_blocks<'a>(name: &str, function_name: Name, args: &'a SeparateArguments<'a>) -> KetosResult<DeclAvatar> {
     let mut avatar = DeclAvatar {
         name: name.to_string(),
         exports_blocks: vec![],
@@ -63,5 +80,5 @@ fn declare_avatar(_name_store: &NameStore, function_name: Name, args: SeparateAr
         }
     }
 
-    Ok(avatar.into())
+    Ok(avatar)
 }
@@ -144,9 +144,13 @@ fn declare_set_parameter(name_store: &NameStore, function_name: Name, args: Sepa
             parameter,
             value: Some(*v),
         },
+        Value::String(v) => DeclParameterDrive::SetEnum {
+            parameter,
+            value: v.to_string(),
+        },
         v => {
             return Err(Error::ExecError(ExecError::TypeError {
-                expected: "int, bool, or float",
+                expected: "int, bool, float, or enum value name",
                 found: v.type_name(),
                 value: Some(v.clone()),
             }))
@@ -10,7 +10,7 @@ use crate::decl_v2::{
     },
     sexpr::{
         argument::SeparateArguments,
-        da::parameter::expect_parameter_reference,
+        da::parameter::{expect_parameter_reference, expect_strings},
         error::{DeclSexprError, KetosResult},
         register_function, KetosValueExt,
     },
@@ -25,21 +25,21 @@ pub fn register_layer_basic_function(scope: &Scope) {
         "group-layer",
         declare_group_layer,
         Arity::Min(1),
-        Some(&["driven-by", "default-mesh", "copy"]),
+        Some(&["driven-by", "default-mesh", "copy", "allow-overlap"]),
     );
     register_function(
         scope,
         "switch-layer",
         declare_switch_layer,
         Arity::Min(1),
-        Some(&["driven-by", "with-gate", "default-mesh"]),
+        Some(&["driven-by", "with-gate", "default-mesh", "allow-overlap"]),
     );
     register_function(
         scope,
         "puppet-layer",
         declare_puppet_layer,
         Arity::Min(1),
-        Some(&["driven-by", "default-mesh", "animation"]),
+        Some(&["driven-by", "default-mesh", "animation", "allow-overlap"]),
     );
 
     // option functions
@@ -92,6 +92,7 @@ fn declare_group_layer(name_store: &NameStore, function_name: Name, args: Separa
     let driven_by: &Value = args.exact_kwarg_expect("driven-by")?;
     let default_mesh: Option<&str> = args.exact_kwarg("default-mesh")?;
     let copy_mode: Option<&Value> = args.exact_kwarg("copy")?;
+    let allow_overlap: Option<&Value> = args.exact_kwarg("allow-overlap")?;
 
     let mut default = None;
     let mut options = vec![];
@@ -122,6 +123,7 @@ fn declare_group_layer(name_store: &NameStore, function_name: Name, args: Separa
         copy_mode: copy_mode.map(|v| expect_copy_mode(name_store, v)).transpose()?,
         default,
         options,
+        allow_overlap: allow_overlap.map(expect_strings).transpose()?.unwrap_or_default(),
     })
     .into())
 }
@@ -131,6 +133,7 @@ fn declare_switch_layer(name_store: &NameStore, function_name: Name, args: Separ
     let driven_by: Option<&Value> = args.exact_kwarg("driven-by")?;
     let with_gate: Option<&str> = args.exact_kwarg("with-gate")?;
     let default_mesh: Option<&str> = args.exact_kwarg("default-mesh")?;
+    let allow_overlap: Option<&Value> = args.exact_kwarg("allow-overlap")?;
 
     let mut disabled = None;
     let mut enabled = None;
@@ -173,6 +176,7 @@ fn declare_switch_layer(name_store: &NameStore, function_name: Name, args: Separ
         default_mesh: default_mesh.map(|dm| dm.to_string()),
         disabled,
         enabled,
+        allow_overlap: allow_overlap.map(expect_strings).transpose()?.unwrap_or_default(),
     })
     .into())
 }
@@ -182,6 +186,7 @@ fn declare_puppet_layer(name_store: &NameStore, function_name: Name, args: Separ
     let driven_by: &Value = args.exact_kwarg_expect("driven-by")?;
     let default_mesh: Option<&str> = args.exact_kwarg("default-mesh")?;
     let animation_asset: Option<&str> = args.exact_kwarg("animation")?;
+    let allow_overlap: Option<&Value> = args.exact_kwarg("allow-overlap")?;
 
     let mut keyframes = vec![];
     for option_value in args.args_after_recursive(function_name, 1)? {
@@ -205,6 +210,7 @@ fn declare_puppet_layer(name_store: &NameStore, function_name: Name, args: Separ
         default_mesh: default_mesh.map(|dm| dm.to_string()),
         animation_asset: animation_asset.map(|a| a.to_string()),
         keyframes,
+        allow_overlap: allow_overlap.map(expect_strings).transpose()?.unwrap_or_default(),
     })
     .into())
 }
@@ -9,7 +9,10 @@ use crate::decl_v2::{
     },
     sexpr::{
         argument::SeparateArguments,
-        da::{layer_basic::take_option_target, parameter::expect_parameter_reference},
+        da::{
+            layer_basic::take_option_target,
+            parameter::{expect_parameter_reference, expect_strings},
+        },
         error::{DeclSexprError, KetosResult},
         register_function, KetosValueExt,
     },
@@ -19,7 +22,13 @@ use ketos::{Arity, Error, ExecError, Name, NameStore, Scope, Value};
 
 pub fn register_layer_raw_function(scope: &Scope) {
     // layer functions
-    register_function(scope, "raw-layer", declare_raw_layer, Arity::Min(1), Some(&["default"]));
+    register_function(
+        scope,
+        "raw-layer",
+        declare_raw_layer,
+        Arity::Min(1),
+        Some(&["default", "allow-overlap"]),
+    );
     register_function(scope, "state", declare_state, Arity::Min(2), Some(&[]));
 
     register_function(
@@ -69,6 +78,7 @@ pub fn register_layer_raw_function(scope: &Scope) {
 fn declare_raw_layer(_name_store: &NameStore, function_name: Name, args: SeparateArguments) -> KetosResult<Value> {
     let name: &str = args.exact_arg(function_name, 0)?;
     let default: Option<&str> = args.exact_kwarg("default")?;
+    let allow_overlap: Option<&Value> = args.exact_kwarg("allow-overlap")?;
 
     let mut states = vec![];
     for state_value in args.args_after_recursive(function_name, 1)? {
@@ -78,6 +88,7 @@ fn declare_raw_layer(_name_store: &NameStore, function_name: Name, args: Separat
         name: name.to_string(),
         default: default.map(|d| d.to_string()),
         states,
+        allow_overlap: allow_overlap.map(expect_strings).transpose()?.unwrap_or_default(),
     })
     .into())
 }
@@ -317,9 +328,15 @@ fn expect_condition(
                 .ok_or_else(|| Error::Custom(DeclSexprError::InvalidCondition.into()))?,
         ),
         Value::Float(fv) => DeclRawLayerTransitionCondition::Float(parameter, ordering, *fv),
+        Value::String(sv) => match ordering {
+            DeclRawLayerTransitionOrdering::Equal | DeclRawLayerTransitionOrdering::NotEqual => {
+                DeclRawLayerTransitionCondition::Enum(parameter, ordering, sv.to_string())
+            }
+            _ => return Err(Error::Custom(DeclSexprError::InvalidCondition.into())),
+        },
         other => {
             return Err(Error::ExecError(ExecError::TypeError {
-                expected: "int, bool, or float",
+                expected: "int, bool, float, or enum value name",
                 found: other.type_name(),
                 value: Some(other.clone()),
             }))
@@ -0,0 +1,388 @@
+use crate::decl_v2::{
+    data::{
+        asset::DeclAssets,
+        attachment::{DeclAttachmentValue, DeclAttachments},
+        controller::DeclFxController,
+        driver::DeclParameterDrive,
+        export::{DeclExport, DeclExports},
+        layer::{
+            DeclControllerLayer, DeclGroupOption, DeclGroupOptionTarget, DeclRawLayerAnimation,
+            DeclRawLayerAnimationKind, DeclRawLayerBlendTreeType, DeclRawLayerTransitionCondition,
+        },
+        menu::{DeclMenuElement, DeclPuppetAxis, DeclPuppetTarget, DeclPuppetType, DeclSubMenu},
+        parameter::{DeclParameter, DeclParameterReference, DeclParameters},
+        StaticTypeName,
+    },
+    sexpr::{
+        argument::SeparateArguments,
+        error::{DeclSexprError, KetosResult},
+        register_function, KetosValueExt,
+    },
+};
+
+use ketos::{Arity, Error, Name, NameStore, Scope, Value};
+
+pub fn register_namespace_function(scope: &Scope) {
+    register_function(scope, "namespace", declare_namespace, Arity::Min(1), Some(&[]));
+}
+
+fn declare_namespace(_name_store: &NameStore, function_name: Name, args: SeparateArguments) -> KetosResult<Value> {
+    let namespace: &str = args.exact_arg(function_name, 0)?;
+    if namespace.is_empty() {
+        return Err(Error::Custom(DeclSexprError::EmptyNamespace.into()));
+    }
+
+    let mut blocks: Vec<Value> = vec![];
+    for block in args.args_after_recursive(function_name, 1)? {
+        let namespaced_block = match block.type_name() {
+            DeclExports::TYPE_NAME => {
+                let mut exports = block.downcast_foreign_ref::<&DeclExports>()?.clone();
+                exports.apply_namespace(namespace);
+                exports.into()
+            }
+            DeclParameters::TYPE_NAME => {
+                let mut parameters = block.downcast_foreign_ref::<&DeclParameters>()?.clone();
+                parameters.apply_namespace(namespace);
+                parameters.into()
+            }
+            DeclFxController::TYPE_NAME => {
+                let mut fx_controller = block.downcast_foreign_ref::<&DeclFxController>()?.clone();
+                fx_controller.apply_namespace(namespace);
+                fx_controller.into()
+            }
+            DeclSubMenu::TYPE_NAME => {
+                let mut menu = block.downcast_foreign_ref::<&DeclSubMenu>()?.clone();
+                menu.apply_namespace(namespace);
+                menu.into()
+            }
+            DeclAttachments::TYPE_NAME => {
+                let mut attachments = block.downcast_foreign_ref::<&DeclAttachments>()?.clone();
+                attachments.apply_namespace(namespace);
+                attachments.into()
+            }
+            // assets refer to Unity-side objects, so they are kept as-is
+            DeclAssets::TYPE_NAME => block.clone(),
+            _ => {
+                return Err(Error::Custom(
+                    DeclSexprError::UnexpectedTypeValue(block.type_name().to_string(), "avatar element".to_string())
+                        .into(),
+                ))
+            }
+        };
+        blocks.push(namespaced_block);
+    }
+
+    Ok(blocks.into())
+}
+
+fn qualify(namespace: &str, name: &mut String) {
+    *name = format!("{namespace}/{name}");
+}
+
+// nested namespaces are applied from inside out, so the outermost one comes first
+// only parameter references can escape with da/global; layer and gate names are always qualified
+trait Namespaced {
+    fn apply_namespace(&mut self, namespace: &str);
+}
+
+impl<T: Namespaced> Namespaced for Option<T> {
+    fn apply_namespace(&mut self, namespace: &str) {
+        if let Some(value) = self {
+            value.apply_namespace(namespace);
+        }
+    }
+}
+
+impl<T: Namespaced> Namespaced for Vec<T> {
+    fn apply_namespace(&mut self, namespace: &str) {
+        for value in self {
+            value.apply_namespace(namespace);
+        }
+    }
+}
+
+impl Namespaced for DeclParameterReference {
+    fn apply_namespace(&mut self, namespace: &str) {
+        // PhysBone and provided parameters have fixed names, and Global escapes namespaces
+        if let DeclParameterReference::Primitive(name) | DeclParameterReference::Contact(name) = self {
+            qualify(namespace, name);
+        }
+    }
+}
+
+impl Namespaced for DeclParameters {
+    fn apply_namespace(&mut self, namespace: &str) {
+        for parameter in &mut self.parameters {
+            match parameter {
+                DeclParameter::Primitive(primitive) => qualify(namespace, &mut primitive.name),
+                DeclParameter::Enum(enum_parameter) => qualify(namespace, &mut enum_parameter.name),
+                DeclParameter::Contact(contact) => qualify(namespace, &mut contact.name),
+                _ => (),
+            }
+        }
+    }
+}
+
+impl Namespaced for DeclExports {
+    fn apply_namespace(&mut self, namespace: &str) {
+        for export in &mut self.exports {
+            match export {
+                DeclExport::Gate(gate) => qualify(namespace, gate),
+                DeclExport::Guard(gate, parameter) => {
+                    qualify(namespace, gate);
+                    parameter.apply_namespace(namespace);
+                }
+            }
+        }
+    }
+}
+
+impl Namespaced for DeclFxController {
+    fn apply_namespace(&mut self, namespace: &str) {
+        self.layers.apply_namespace(namespace);
+    }
+}
+
+impl Namespaced for DeclControllerLayer {
+    fn apply_namespace(&mut self, namespace: &str) {
+        match self {
+            DeclControllerLayer::Group(group) => {
+                qualify(namespace, &mut group.name);
+                group.driven_by.apply_namespace(namespace);
+                group.default.apply_namespace(namespace);
+                group.options.apply_namespace(namespace);
+            }
+            DeclControllerLayer::Switch(switch) => {
+                qualify(namespace, &mut switch.name);
+                switch.driven_by.apply_namespace(namespace);
+                if let Some(gate) = &mut switch.with_gate {
+                    qualify(namespace, gate);
+                }
+                switch.disabled.apply_namespace(namespace);
+                switch.enabled.apply_namespace(namespace);
+            }
+            DeclControllerLayer::Puppet(puppet) => {
+                qualify(namespace, &mut puppet.name);
+                puppet.driven_by.apply_namespace(namespace);
+                puppet.keyframes.apply_namespace(namespace);
+            }
+            DeclControllerLayer::Raw(raw) => {
+                qualify(namespace, &mut raw.name);
+                for state in &mut raw.states {
+                    match &mut state.kind {
+                        DeclRawLayerAnimationKind::Clip { animation, speed, time } => {
+                            animation.apply_namespace(namespace);
+                            speed.1.apply_namespace(namespace);
+                            time.apply_namespace(namespace);
+                        }
+                        DeclRawLayerAnimationKind::BlendTree { tree_type, fields } => {
+                            match tree_type {
+                                DeclRawLayerBlendTreeType::Linear(x) => x.apply_namespace(namespace),
+                                DeclRawLayerBlendTreeType::Simple2D(x, y)
+                                | DeclRawLayerBlendTreeType::Freeform2D(x, y)
+                                | DeclRawLayerBlendTreeType::Cartesian2D(x, y) => {
+                                    x.apply_namespace(namespace);
+                                    y.apply_namespace(namespace);
+                                }
+                            }
+                            for field in fields {
+                                field.animation.apply_namespace(namespace);
+                            }
+                        }
+                    }
+                    for transition in &mut state.transitions {
+                        transition.conditions.apply_namespace(namespace);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Namespaced for DeclGroupOption {
+    fn apply_namespace(&mut self, namespace: &str) {
+        for target in &mut self.targets {
+            if let DeclGroupOptionTarget::ParameterDrive(drive) = target {
+                drive.apply_namespace(namespace);
+            }
+        }
+    }
+}
+
+impl Namespaced for DeclRawLayerAnimation {
+    fn apply_namespace(&mut self, namespace: &str) {
+        let DeclRawLayerAnimation::Inline(inline) = self else {
+            return;
+        };
+        for target in &mut inline.targets {
+            if let DeclGroupOptionTarget::ParameterDrive(drive) = target {
+                drive.apply_namespace(namespace);
+            }
+        }
+    }
+}
+
+impl Namespaced for DeclRawLayerTransitionCondition {
+    fn apply_namespace(&mut self, namespace: &str) {
+        match self {
+            DeclRawLayerTransitionCondition::Zero(parameter, _)
+            | DeclRawLayerTransitionCondition::Bool(parameter, _)
+            | DeclRawLayerTransitionCondition::Int(parameter, _, _)
+            | DeclRawLayerTransitionCondition::Float(parameter, _, _)
+            | DeclRawLayerTransitionCondition::Enum(parameter, _, _) => parameter.apply_namespace(namespace),
+        }
+    }
+}
+
+impl Namespaced for DeclParameterDrive {
+    fn apply_namespace(&mut self, namespace: &str) {
+        match self {
+            DeclParameterDrive::Group(dg) => qualify(namespace, &mut dg.group),
+            DeclParameterDrive::Switch(ds) => qualify(namespace, &mut ds.switch),
+            DeclParameterDrive::Puppet(dp) => qualify(namespace, &mut dp.puppet),
+            DeclParameterDrive::SetInt { parameter, .. }
+            | DeclParameterDrive::SetEnum { parameter, .. }
+            | DeclParameterDrive::SetBool { parameter, .. }
+            | DeclParameterDrive::SetFloat { parameter, .. }
+            | DeclParameterDrive::AddInt { parameter, .. }
+            | DeclParameterDrive::AddFloat { parameter, .. }
+            | DeclParameterDrive::RandomInt { parameter, .. }
+            | DeclParameterDrive::RandomBool { parameter, .. }
+            | DeclParameterDrive::RandomFloat { parameter, .. } => parameter.apply_namespace(namespace),
+            DeclParameterDrive::Copy { from, to, .. } => {
+                from.apply_namespace(namespace);
+                to.apply_namespace(namespace);
+            }
+        }
+    }
+}
+
+impl Namespaced for DeclSubMenu {
+    fn apply_namespace(&mut self, namespace: &str) {
+        self.elements.apply_namespace(namespace);
+    }
+}
+
+impl Namespaced for DeclMenuElement {
+    fn apply_namespace(&mut self, namespace: &str) {
+        match self {
+            DeclMenuElement::SubMenu(submenu) => submenu.apply_namespace(namespace),
+            DeclMenuElement::Boolean(boolean) => boolean.parameter_drive.apply_namespace(namespace),
+            DeclMenuElement::Puppet(puppet) => match puppet.puppet_type.as_mut() {
+                DeclPuppetType::Radial(axis) => axis.apply_namespace(namespace),
+                DeclPuppetType::TwoAxis { horizontal, vertical } => {
+                    horizontal.apply_namespace(namespace);
+                    vertical.apply_namespace(namespace);
+                }
+                DeclPuppetType::FourAxis { up, down, left, right } => {
+                    up.apply_namespace(namespace);
+                    down.apply_namespace(namespace);
+                    left.apply_namespace(namespace);
+                    right.apply_namespace(namespace);
+                }
+            },
+        }
+    }
+}
+
+impl Namespaced for DeclPuppetAxis {
+    fn apply_namespace(&mut self, namespace: &str) {
+        match &mut self.target {
+            DeclPuppetTarget::Puppet(dp) => qualify(namespace, &mut dp.puppet),
+            DeclPuppetTarget::Parameter(parameter) => parameter.apply_namespace(namespace),
+        }
+    }
+}
+
+// only parameter references are qualified; other values refer to Unity-side objects
+impl Namespaced for DeclAttachments {
+    fn apply_namespace(&mut self, namespace: &str) {
+        for attachment in &mut self.attachments {
+            for property in &mut attachment.properties {
+                property.parameters.apply_namespace(namespace);
+                for value in property.keywords.values_mut() {
+                    value.apply_namespace(namespace);
+                }
+            }
+        }
+    }
+}
+
+impl Namespaced for DeclAttachmentValue {
+    fn apply_namespace(&mut self, namespace: &str) {
+        match self {
+            DeclAttachmentValue::Parameter(parameter) => parameter.apply_namespace(namespace),
+            DeclAttachmentValue::UntypedList(values) => values.apply_namespace(namespace),
+            DeclAttachmentValue::Map(pairs) => {
+                for (key, value) in pairs {
+                    key.apply_namespace(namespace);
+                    value.apply_namespace(namespace);
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::decl_v2::{
+        data::{
+            export::{DeclExport, DeclExports},
+            parameter::{
+                DeclParameter, DeclParameterReference, DeclParameters, DeclPrimitiveParameter,
+                DeclPrimitiveParameterType,
+            },
+        },
+        sexpr::test::eval_da_value,
+    };
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn prefixes_parameters() {
+        assert_eq!(
+            eval_da_value::<DeclParameters>(r#"(first (da/namespace "ns" (da/parameters (da/bool "hoge"))))"#),
+            DeclParameters {
+                parameters: vec![DeclParameter::Primitive(DeclPrimitiveParameter {
+                    ty: DeclPrimitiveParameterType::Bool(None),
+                    name: "ns/hoge".to_string(),
+                    scope: None,
+                    save: None,
+                    unique: None,
+                    range: (None, None),
+                })]
+            }
+        );
+    }
+
+    #[test]
+    fn nests_namespaces() {
+        assert_eq!(
+            eval_da_value::<DeclExports>(
+                r#"(first (da/namespace "outer" (da/namespace "inner" (da/exports (da/gate "hoge")))))"#
+            ),
+            DeclExports {
+                exports: vec![DeclExport::Gate("outer/inner/hoge".to_string())]
+            }
+        );
+    }
+
+    #[test]
+    fn keeps_global_references() {
+        assert_eq!(
+            eval_da_value::<DeclExports>(
+                r#"(first (da/namespace "ns" (da/exports (da/guard "hoge" "fuga") (da/guard "hoge" (da/global "fuga")))))"#
+            ),
+            DeclExports {
+                exports: vec![
+                    DeclExport::Guard(
+                        "ns/hoge".to_string(),
+                        DeclParameterReference::Primitive("ns/fuga".into())
+                    ),
+                    DeclExport::Guard("ns/hoge".to_string(), DeclParameterReference::Global("fuga".into())),
+                ]
+            }
+        );
+    }
+}
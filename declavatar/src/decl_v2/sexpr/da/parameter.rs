@@ -1,8 +1,9 @@
 use crate::decl_v2::{
     data::{
         parameter::{
-            DeclParameter, DeclParameterReference, DeclParameters, DeclPhysBoneParameter, DeclPhysBoneParameterKind,
-            DeclPrimitiveParameter, DeclPrimitiveParameterScope, DeclPrimitiveParameterType, DeclProvidedParameterKind,
+            DeclContactParameter, DeclContactReceiverKind, DeclEnumParameter, DeclParameter, DeclParameterReference,
+            DeclParameters, DeclPhysBoneParameter, DeclPhysBoneParameterKind, DeclPrimitiveParameter,
+            DeclPrimitiveParameterScope, DeclPrimitiveParameterType, DeclProvidedParameterKind,
         },
         StaticTypeName,
     },
@@ -41,13 +42,24 @@ pub fn expect_parameter_reference(name_store: &NameStore, value: &Value) -> Keto
 
 pub fn register_parameter_function(scope: &Scope) {
     const PARAMETER_KEYWORDS: &[&str] = &["save", "default", "scope", "unique"];
+    const NUMERIC_KEYWORDS: &[&str] = &["save", "default", "scope", "unique", "min", "max"];
     register_function(scope, "parameters", declare_parameters, Arity::Min(0), Some(&[]));
     register_function(scope, "bool", declare_bool, Arity::Exact(1), Some(PARAMETER_KEYWORDS));
-    register_function(scope, "int", declare_int, Arity::Exact(1), Some(PARAMETER_KEYWORDS));
-    register_function(scope, "float", declare_float, Arity::Exact(1), Some(PARAMETER_KEYWORDS));
+    register_function(scope, "int", declare_int, Arity::Exact(1), Some(NUMERIC_KEYWORDS));
+    register_function(scope, "float", declare_float, Arity::Exact(1), Some(NUMERIC_KEYWORDS));
+    register_function(scope, "enum", declare_enum, Arity::Min(2), Some(PARAMETER_KEYWORDS));
     register_function(scope, "vrc-paramset", declare_vrc_paramset, Arity::Min(0), Some(&[]));
     register_function(scope, "pb-paramset", declare_pb_paramset, Arity::Exact(1), Some(&[]));
     register_function(scope, "pb-param", declare_pb_param, Arity::Exact(2), Some(&[]));
+    register_function(
+        scope,
+        "contact-param",
+        declare_contact_param,
+        Arity::Exact(2),
+        Some(&["root", "radius", "tags", "allow-self", "allow-others", "local-only"]),
+    );
+    register_function(scope, "contact", declare_contact, Arity::Exact(1), Some(&[]));
+    register_function(scope, "global", declare_global, Arity::Exact(1), Some(&[]));
 }
 
 fn declare_parameters(_name_store: &NameStore, function_name: Name, args: SeparateArguments) -> KetosResult<Value> {
@@ -71,6 +83,7 @@ fn declare_bool(name_store: &NameStore, function_name: Name, args: SeparateArgum
         scope: scope.map(|s| expect_scope(name_store, s)).transpose()?,
         save,
         unique,
+        range: (None, None),
     })
     .into())
 }
@@ -81,6 +94,8 @@ fn declare_int(name_store: &NameStore, function_name: Name, args: SeparateArgume
     let default: Option<u8> = args.exact_kwarg("default")?;
     let scope: Option<&Value> = args.exact_kwarg("scope")?;
     let unique: Option<bool> = args.exact_kwarg("unique")?;
+    let min: Option<u8> = args.exact_kwarg("min")?;
+    let max: Option<u8> = args.exact_kwarg("max")?;
 
     Ok(DeclParameter::Primitive(DeclPrimitiveParameter {
         ty: DeclPrimitiveParameterType::Int(default),
@@ -88,6 +103,7 @@ fn declare_int(name_store: &NameStore, function_name: Name, args: SeparateArgume
         scope: scope.map(|s| expect_scope(name_store, s)).transpose()?,
         save,
         unique,
+        range: (min.map(f64::from), max.map(f64::from)),
     })
     .into())
 }
@@ -98,6 +114,8 @@ fn declare_float(name_store: &NameStore, function_name: Name, args: SeparateArgu
     let default: Option<f64> = args.exact_kwarg("default")?;
     let scope: Option<&Value> = args.exact_kwarg("scope")?;
     let unique: Option<bool> = args.exact_kwarg("unique")?;
+    let min: Option<f64> = args.exact_kwarg("min")?;
+    let max: Option<f64> = args.exact_kwarg("max")?;
 
     Ok(DeclParameter::Primitive(DeclPrimitiveParameter {
         ty: DeclPrimitiveParameterType::Float(default),
@@ -105,6 +123,36 @@ fn declare_float(name_store: &NameStore, function_name: Name, args: SeparateArgu
         scope: scope.map(|s| expect_scope(name_store, s)).transpose()?,
         save,
         unique,
+        range: (min, max),
+    })
+    .into())
+}
+
+fn declare_enum(name_store: &NameStore, function_name: Name, args: SeparateArguments) -> KetosResult<Value> {
+    let name: &str = args.exact_arg(function_name, 0)?;
+    let save: Option<bool> = args.exact_kwarg("save")?;
+    let default: Option<&str> = args.exact_kwarg("default")?;
+    let scope: Option<&Value> = args.exact_kwarg("scope")?;
+    let unique: Option<bool> = args.exact_kwarg("unique")?;
+
+    let mut values = vec![];
+    for value in args.args_after_recursive(function_name, 1)? {
+        let Value::String(value_name) = value else {
+            return Err(Error::Custom(
+                DeclSexprError::UnexpectedTypeValue(value.type_name().to_string(), "enum value name".to_string())
+                    .into(),
+            ));
+        };
+        values.push(value_name.to_string());
+    }
+
+    Ok(DeclParameter::Enum(DeclEnumParameter {
+        name: name.to_string(),
+        values,
+        default: default.map(|d| d.to_string()),
+        scope: scope.map(|s| expect_scope(name_store, s)).transpose()?,
+        save,
+        unique,
     })
     .into())
 }
@@ -157,6 +205,76 @@ fn declare_pb_param(name_store: &NameStore, function_name: Name, args: SeparateA
     Ok(DeclParameterReference::PhysBone(prefix.to_string(), expect_pb_kind(name_store, kind_value)?).into())
 }
 
+fn declare_contact_param(name_store: &NameStore, function_name: Name, args: SeparateArguments) -> KetosResult<Value> {
+    let name: &str = args.exact_arg(function_name, 0)?;
+    let kind_value: &Value = args.exact_arg(function_name, 1)?;
+    let root: Option<&str> = args.exact_kwarg("root")?;
+    let radius: Option<f64> = args.exact_kwarg("radius")?;
+    let tags: Option<&Value> = args.exact_kwarg("tags")?;
+    let allow_self: Option<bool> = args.exact_kwarg("allow-self")?;
+    let allow_others: Option<bool> = args.exact_kwarg("allow-others")?;
+    let local_only: Option<bool> = args.exact_kwarg("local-only")?;
+
+    Ok(DeclParameter::Contact(DeclContactParameter {
+        name: name.to_string(),
+        kind: expect_contact_kind(name_store, kind_value)?,
+        root: root.map(|r| r.to_string()),
+        radius,
+        tags: tags.map(expect_strings).transpose()?.unwrap_or_default(),
+        allow_self,
+        allow_others,
+        local_only,
+    })
+    .into())
+}
+
+fn declare_contact(_name_store: &NameStore, function_name: Name, args: SeparateArguments) -> KetosResult<Value> {
+    let name: &str = args.exact_arg(function_name, 0)?;
+    Ok(DeclParameterReference::Contact(name.to_string()).into())
+}
+
+fn expect_contact_kind(name_store: &NameStore, value: &Value) -> KetosResult<DeclContactReceiverKind> {
+    let Value::Name(name) = value else {
+        return Err(Error::Custom(
+            DeclSexprError::UnexpectedTypeValue(value.type_name().to_string(), "contact receiver type".to_string())
+                .into(),
+        ));
+    };
+
+    match name_store.get(*name).parse() {
+        Ok(kind) => Ok(kind),
+        Err(n) => Err(Error::Custom(DeclSexprError::InvalidContactKind(n).into())),
+    }
+}
+
+pub fn expect_strings(value: &Value) -> KetosResult<Vec<String>> {
+    let string_values = match value {
+        Value::String(s) => return Ok(vec![s.to_string()]),
+        Value::List(string_values) => string_values,
+        v => {
+            return Err(Error::Custom(
+                DeclSexprError::UnexpectedTypeValue(v.type_name().to_string(), "string or list".to_string()).into(),
+            ))
+        }
+    };
+
+    let mut strings = vec![];
+    for string_value in string_values.iter() {
+        let Value::String(s) = string_value else {
+            return Err(Error::Custom(
+                DeclSexprError::UnexpectedTypeValue(string_value.type_name().to_string(), "string".to_string()).into(),
+            ));
+        };
+        strings.push(s.to_string());
+    }
+    Ok(strings)
+}
+
+fn declare_global(_name_store: &NameStore, function_name: Name, args: SeparateArguments) -> KetosResult<Value> {
+    let name: &str = args.exact_arg(function_name, 0)?;
+    Ok(DeclParameterReference::Global(name.to_string()).into())
+}
+
 fn expect_pb_kind(name_store: &NameStore, value: &Value) -> KetosResult<DeclPhysBoneParameterKind> {
     let Value::Name(name) = value else {
         return Err(Error::Custom(DeclSexprError::MustBeScope.into()));
@@ -172,8 +290,8 @@ fn expect_pb_kind(name_store: &NameStore, value: &Value) -> KetosResult<DeclPhys
 mod test {
     use crate::decl_v2::{
         data::parameter::{
-            DeclParameter, DeclParameters, DeclPrimitiveParameter, DeclPrimitiveParameterScope,
-            DeclPrimitiveParameterType,
+            DeclContactParameter, DeclContactReceiverKind, DeclEnumParameter, DeclParameter, DeclParameters,
+            DeclPrimitiveParameter, DeclPrimitiveParameterScope, DeclPrimitiveParameterType,
         },
         sexpr::test::eval_da_value,
     };
@@ -236,6 +354,66 @@ mod test {
         );
     }
 
+    #[test]
+    fn reads_enum() {
+        assert_eq!(
+            eval_da_value::<DeclParameter>(r#"(da/enum "hoge" "none" (list "fuga" "piyo") :default "fuga")"#),
+            DeclParameter::Enum(DeclEnumParameter {
+                name: "hoge".to_string(),
+                values: vec!["none".to_string(), "fuga".to_string(), "piyo".to_string()],
+                default: Some("fuga".to_string()),
+                scope: None,
+                save: None,
+                unique: None,
+            })
+        );
+    }
+
+    #[test]
+    fn parses_range() {
+        assert_eq!(
+            eval_da_value::<DeclParameter>(r#"(da/int "hoge" :min 1 :max 4)"#),
+            DeclParameter::Primitive(DeclPrimitiveParameter {
+                ty: DeclPrimitiveParameterType::Int(None),
+                name: "hoge".to_string(),
+                scope: None,
+                save: None,
+                unique: None,
+                range: (Some(1.0), Some(4.0)),
+            })
+        );
+        assert_eq!(
+            eval_da_value::<DeclParameter>(r#"(da/float "hoge" :max 0.5)"#),
+            DeclParameter::Primitive(DeclPrimitiveParameter {
+                ty: DeclPrimitiveParameterType::Float(None),
+                name: "hoge".to_string(),
+                scope: None,
+                save: None,
+                unique: None,
+                range: (None, Some(0.5)),
+            })
+        );
+    }
+
+    #[test]
+    fn reads_contact_param() {
+        assert_eq!(
+            eval_da_value::<DeclParameter>(
+                r#"(da/contact-param "hoge" 'proximity :radius 0.2 :tags (list "Hand" "Finger") :allow-self false)"#
+            ),
+            DeclParameter::Contact(DeclContactParameter {
+                name: "hoge".to_string(),
+                kind: DeclContactReceiverKind::Proximity,
+                root: None,
+                radius: Some(0.2),
+                tags: vec!["Hand".to_string(), "Finger".to_string()],
+                allow_self: Some(false),
+                allow_others: None,
+                local_only: None,
+            })
+        );
+    }
+
     #[test]
     fn parses_scope() {
         assert_eq!(
@@ -262,6 +440,7 @@ mod test {
                 scope: None,
                 save: Some(true),
                 unique: None,
+                range: (None, None),
             })
         );
     }
@@ -276,6 +455,7 @@ mod test {
                 scope: None,
                 save: None,
                 unique: Some(true),
+                range: (None, None),
             })
         );
     }
@@ -287,6 +467,7 @@ mod test {
             scope: None,
             save: None,
             unique: None,
+            range: (None, None),
         })
     }
 
@@ -297,6 +478,7 @@ mod test {
             scope: Some(s),
             save: None,
             unique: None,
+            range: (None, None),
         })
     }
 }
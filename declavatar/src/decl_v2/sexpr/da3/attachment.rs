@@ -6,11 +6,19 @@ use crate::decl_v2::{
     sexpr::{argument::SeparateArguments, error::KetosResult, register_function, KetosValueExt},
 };
 
+use std::collections::BTreeMap;
+
 use ketos::{Arity, Error, Name, NameStore, Scope, Value};
 
 pub fn register_attachment_function(scope: &Scope) {
     register_function(scope, "attachments", declare_attachments, Arity::Min(0), Some(&[]));
-    register_function(scope, "attachment", define_attachment, Arity::Min(1), Some(&[]));
+    register_function(
+        scope,
+        "attachment",
+        define_attachment,
+        Arity::Min(1),
+        Some(&["version"]),
+    );
     register_function(scope, "property", define_property, Arity::Min(1), None);
 }
 
@@ -24,6 +32,7 @@ fn declare_attachments(_name_store: &NameStore, function_name: Name, args: Separ
 
 fn define_attachment(_name_store: &NameStore, function_name: Name, args: SeparateArguments) -> KetosResult<Value> {
     let name: &str = args.exact_arg(function_name, 0)?;
+    let version: Option<u32> = args.exact_kwarg("version")?;
 
     let mut properties = vec![];
     for decl_property in args.args_after_recursive(function_name, 1)? {
@@ -31,6 +40,7 @@ fn define_attachment(_name_store: &NameStore, function_name: Name, args: Separat
     }
     Ok(DeclAttachment {
         name: name.to_string(),
+        version,
         properties,
     }
     .into())
@@ -44,14 +54,19 @@ fn define_property(_name_store: &NameStore, function_name: Name, args: SeparateA
     for decl_paramerter in args.args_after(function_name, 1)? {
         parameters.push(parse_attachment_value(decl_paramerter)?);
     }
+    let mut keywords = BTreeMap::new();
+    for (keyword, decl_value) in args.kwargs() {
+        keywords.insert(keyword.to_string(), parse_attachment_value(decl_value)?);
+    }
     Ok(DeclAttachmentProperty {
         name: name.to_string(),
         parameters,
+        keywords,
     }
     .into())
 }
 
-fn parse_attachment_value(value: &Value) -> KetosResult<DeclAttachmentValue> {
+pub fn parse_attachment_value(value: &Value) -> KetosResult<DeclAttachmentValue> {
     let attachment_value = match value {
         Value::Unit => DeclAttachmentValue::Null,
         Value::Bool(v) => DeclAttachmentValue::Boolean(*v),
@@ -1,9 +1,15 @@
 use crate::decl_v2::{
     data::attachment::DeclAttachmentValue,
-    sexpr::{argument::SeparateArguments, error::KetosResult, register_function},
+    sexpr::{
+        argument::SeparateArguments,
+        da::parameter::expect_parameter_reference,
+        da3::attachment::parse_attachment_value,
+        error::{DeclSexprError, KetosResult},
+        register_function,
+    },
 };
 
-use ketos::{Arity, Name, NameStore, Scope, Value};
+use ketos::{Arity, Error, Name, NameStore, Scope, Value};
 
 pub fn register_value_function(scope: &Scope) {
     register_function(
@@ -36,6 +42,8 @@ pub fn register_value_function(scope: &Scope) {
         Arity::Exact(1),
         Some(&[]),
     );
+    register_function(scope, "parameter", value_parameter, Arity::Exact(1), Some(&[]));
+    register_function(scope, "map", value_map, Arity::Min(0), Some(&[]));
 }
 
 fn value_vector(
@@ -64,3 +72,22 @@ fn value_animation_clip(_name_store: &NameStore, function_name: Name, args: Sepa
     let name: &str = args.exact_arg(function_name, 0)?;
     Ok(DeclAttachmentValue::AnimationClip(name.to_string()).into())
 }
+
+fn value_parameter(name_store: &NameStore, function_name: Name, args: SeparateArguments) -> KetosResult<Value> {
+    let parameter: &Value = args.exact_arg(function_name, 0)?;
+    Ok(DeclAttachmentValue::Parameter(expect_parameter_reference(name_store, parameter)?).into())
+}
+
+fn value_map(_name_store: &NameStore, function_name: Name, args: SeparateArguments) -> KetosResult<Value> {
+    // keys and values are taken alternately, and lists are kept as values
+    let key_values = args.args_after(function_name, 0)?;
+    if key_values.len() % 2 != 0 {
+        return Err(Error::Custom(DeclSexprError::InvalidMapPairs.into()));
+    }
+
+    let mut pairs = vec![];
+    for pair in key_values.chunks(2) {
+        pairs.push((parse_attachment_value(pair[0])?, parse_attachment_value(pair[1])?));
+    }
+    Ok(DeclAttachmentValue::Map(pairs).into())
+}
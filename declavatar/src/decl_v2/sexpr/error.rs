@@ -22,6 +22,9 @@ pub enum DeclSexprError {
     #[error("invalid VRChat-provided parameter type: {0}")]
     InvalidVrchatParameter(String),
 
+    #[error("invalid contact receiver type: {0}")]
+    InvalidContactKind(String),
+
     #[error("invalid copy mode: {0}")]
     InvalidCopyMode(String),
 
@@ -36,4 +39,10 @@ pub enum DeclSexprError {
 
     #[error("localization key {0} not found")]
     LocalizationNotFound(String),
+
+    #[error("map must be constructed from key-value pairs")]
+    InvalidMapPairs,
+
+    #[error("namespace name must not be empty")]
+    EmptyNamespace,
 }
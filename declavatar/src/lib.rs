@@ -2,3 +2,4 @@ pub mod avatar_v2;
 pub mod decl_v2;
 pub mod i18n;
 pub mod log;
+pub mod project;
@@ -0,0 +1,151 @@
+pub mod error;
+
+use crate::{avatar_v2::Transformer, decl_v2::Arguments, project::error::ProjectError};
+
+use std::{
+    collections::BTreeMap,
+    fs::read_to_string,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+pub const MANIFEST_FILENAME: &str = "declavatar.toml";
+
+// relative paths are resolved from the directory containing the manifest
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Manifest {
+    pub entries: Vec<PathBuf>,
+    pub library_paths: Vec<PathBuf>,
+    pub symbols: Vec<String>,
+    pub localizations: Vec<PathBuf>,
+    pub attachment_schemas: Vec<PathBuf>,
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+// symbols are defined in addition to ones in the manifest root
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Profile {
+    pub symbols: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Project {
+    manifest_path: PathBuf,
+    root: PathBuf,
+    manifest: Manifest,
+}
+
+impl Project {
+    pub fn load(manifest_path: impl AsRef<Path>) -> Result<Project, ProjectError> {
+        let manifest_path = manifest_path.as_ref();
+        let manifest_toml =
+            read_to_string(manifest_path).map_err(|e| ProjectError::Io(manifest_path.to_path_buf(), e))?;
+        let manifest =
+            toml::from_str(&manifest_toml).map_err(|e| ProjectError::Manifest(manifest_path.to_path_buf(), e))?;
+        let root = manifest_path.parent().map(Path::to_path_buf).unwrap_or_default();
+
+        Ok(Project {
+            manifest_path: manifest_path.to_path_buf(),
+            root,
+            manifest,
+        })
+    }
+
+    // searches the directory of `start` and its ancestors
+    pub fn discover(start: impl AsRef<Path>) -> Result<Option<Project>, ProjectError> {
+        let start = start.as_ref();
+        let absolute = start
+            .canonicalize()
+            .map_err(|e| ProjectError::Io(start.to_path_buf(), e))?;
+        for directory in absolute.ancestors().filter(|p| p.is_dir()) {
+            let manifest_path = directory.join(MANIFEST_FILENAME);
+            if manifest_path.is_file() {
+                return Project::load(manifest_path).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    // accepts either the manifest itself or any path in the project
+    pub fn find(path: impl AsRef<Path>) -> Result<Project, ProjectError> {
+        let path = path.as_ref();
+        if path.is_file() && path.file_name().is_some_and(|name| name == MANIFEST_FILENAME) {
+            return Project::load(path);
+        }
+        Project::discover(path)?.ok_or_else(|| ProjectError::NotFound(path.to_path_buf()))
+    }
+
+    pub fn manifest_path(&self) -> &Path {
+        &self.manifest_path
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn manifest(&self) -> &Manifest {
+        &self.manifest
+    }
+
+    pub fn entries(&self) -> Vec<PathBuf> {
+        self.resolve_all(&self.manifest.entries)
+    }
+
+    pub fn profile(&self, name: &str) -> Result<&Profile, ProjectError> {
+        self.manifest
+            .profiles
+            .get(name)
+            .ok_or_else(|| ProjectError::UnknownProfile(name.to_string()))
+    }
+
+    pub fn configure_arguments(&self, args: &mut Arguments, profile: Option<&str>) -> Result<(), ProjectError> {
+        let profile_symbols = match profile {
+            Some(name) => &self.profile(name)?.symbols[..],
+            None => &[],
+        };
+
+        for library_path in self.resolve_all(&self.manifest.library_paths) {
+            args.add_library_path(library_path);
+        }
+        for symbol in self.manifest.symbols.iter().chain(profile_symbols) {
+            args.define_symbol(symbol);
+        }
+        for localization_path in self.resolve_all(&self.manifest.localizations) {
+            let localization_json =
+                read_to_string(&localization_path).map_err(|e| ProjectError::Io(localization_path.clone(), e))?;
+            let localizations: BTreeMap<String, String> = serde_json::from_str(&localization_json)
+                .map_err(|e| ProjectError::Localization(localization_path.clone(), e))?;
+            for (key, value) in &localizations {
+                args.define_localization(key, value);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn register_attachment_schemas(&self, transformer: &mut Transformer) -> Result<(), ProjectError> {
+        for schema_path in self.resolve_all(&self.manifest.attachment_schemas) {
+            if schema_path.is_dir() {
+                transformer.load_arbittach_schemas(&schema_path)?;
+            } else {
+                transformer.load_arbittach_schema_file(&schema_path)?;
+            }
+        }
+        Ok(())
+    }
+
+    // files and directories which affect compilation, except entries and their modules
+    pub fn dependent_paths(&self) -> Vec<PathBuf> {
+        let mut paths = vec![self.manifest_path.clone()];
+        paths.extend(self.resolve_all(&self.manifest.library_paths));
+        paths.extend(self.resolve_all(&self.manifest.localizations));
+        paths.extend(self.resolve_all(&self.manifest.attachment_schemas));
+        paths
+    }
+
+    fn resolve_all(&self, paths: &[PathBuf]) -> Vec<PathBuf> {
+        paths.iter().map(|p| self.root.join(p)).collect()
+    }
+}
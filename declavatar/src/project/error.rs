@@ -0,0 +1,28 @@
+use crate::avatar_v2::error::SchemaError;
+
+use std::{io::Error as IoError, path::PathBuf};
+
+use serde_json::Error as JsonError;
+use thiserror::Error as ThisError;
+use toml::de::Error as TomlError;
+
+#[derive(Debug, ThisError)]
+pub enum ProjectError {
+    #[error("failed to read {0:?}: {1}")]
+    Io(PathBuf, IoError),
+
+    #[error("failed to parse project manifest {0:?}: {1}")]
+    Manifest(PathBuf, TomlError),
+
+    #[error("failed to parse localization file {0:?}: {1}")]
+    Localization(PathBuf, JsonError),
+
+    #[error("project manifest not found from {0:?}")]
+    NotFound(PathBuf),
+
+    #[error("profile {0} is not defined in project manifest")]
+    UnknownProfile(String),
+
+    #[error(transparent)]
+    Schema(#[from] SchemaError),
+}
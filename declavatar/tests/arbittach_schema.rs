@@ -0,0 +1,79 @@
+// schema declaration structs are never constructed
+#![allow(dead_code)]
+
+use std::collections::BTreeMap;
+
+use declavatar::avatar_v2::data::attachment::{
+    schema::{ArbittachProperty, ArbittachSchema, Attachment, ParameterValueType, ValueType},
+    Value,
+};
+use once_cell::sync::Lazy;
+use pretty_assertions::assert_eq;
+
+static TEST_ARBITTACH: Lazy<Attachment> = Lazy::new(|| {
+    serde_json::from_str(include_str!("../../examples/arbittach/game-object.json")).expect("failed to deserialize")
+});
+
+#[derive(ArbittachSchema)]
+#[arbittach(name = "GameObject", version = 3)]
+struct GameObject {
+    transform: Transform,
+    parent: Parent,
+    metadata: Option<Metadata>,
+    toggle: Option<Toggle>,
+}
+
+#[derive(ArbittachProperty)]
+struct Transform {
+    position: [f64; 3],
+    #[arbittach(
+        value_type = ValueType::Enum(vec!["local".into(), "world".into()]),
+        default = Value::String("local".into()),
+    )]
+    space: String,
+    #[arbittach(keyword)]
+    rotation: Option<[f64; 3]>,
+}
+
+#[derive(ArbittachProperty)]
+struct Parent {
+    #[arbittach(value_type = ValueType::GameObject)]
+    parent: String,
+}
+
+#[derive(ArbittachProperty)]
+struct Metadata {
+    entries: BTreeMap<String, Value>,
+}
+
+#[derive(ArbittachProperty)]
+struct Toggle {
+    #[arbittach(value_type = ValueType::Parameter(Some(ParameterValueType::Bool)))]
+    parameter: String,
+    #[arbittach(keyword, value_type = ValueType::Material)]
+    material: Option<String>,
+}
+
+#[test]
+fn derives_same_schema_as_json() {
+    assert_eq!(GameObject::arbittach_schema(), *TEST_ARBITTACH);
+}
+
+#[test]
+fn converts_to_json_schema() {
+    let json_schema = TEST_ARBITTACH.to_json_schema();
+    let property_schemas = json_schema["properties"]["properties"]["items"]["oneOf"]
+        .as_array()
+        .expect("should be array");
+
+    assert_eq!(json_schema["title"], "GameObject");
+    assert_eq!(property_schemas.len(), 4);
+    // omitted space parameter is filled with default
+    assert_eq!(property_schemas[0]["properties"]["parameters"]["minItems"], 2);
+    assert_eq!(
+        json_schema["properties"]["properties"]["allOf"]
+            .as_array()
+            .map(|a| a.len()),
+        Some(2)
+    );
+}
@@ -1,8 +1,24 @@
 use std::{fs::read_to_string, path::PathBuf};
 
 use declavatar::{
-    avatar_v2::{data::attachment::schema::Attachment, Transformer},
-    decl_v2::{compile_declaration, Arguments, DeclarationFormat},
+    avatar_v2::{
+        analysis::{
+            diff::{AvatarDiff, LayerChange, OptionChange, TargetChange},
+            variants::{VariantDifference, VariantReport},
+            xref::CrossReference,
+        },
+        data::{
+            attachment::{schema::Attachment, Value},
+            avatar::Avatar,
+        },
+        error::SchemaError,
+        TransformResult, Transformer,
+    },
+    decl_v2::{
+        compile_declaration, compile_declarations, error::DeclError, Arguments, DeclarationFormat, DeclarationSource,
+        ModuleResolver,
+    },
+    project::{error::ProjectError, Project},
 };
 use once_cell::sync::Lazy;
 use pretty_assertions::assert_eq;
@@ -28,6 +44,44 @@ static TEST_ARBITTACH: Lazy<Attachment> = Lazy::new(|| {
     serde_json::from_str(include_str!("../../examples/arbittach/game-object.json")).expect("failed to deserialize")
 });
 
+static TEST_ARBITTACH_DIR: Lazy<PathBuf> =
+    Lazy::new(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../examples/arbittach"));
+
+static TEST_PROJECT_SOURCE: Lazy<PathBuf> =
+    Lazy::new(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../examples/project/avatar.declisp"));
+
+fn transform_sexpr(source: &str) -> TransformResult {
+    let decl_avatar = compile_declaration(source, DeclarationFormat::Sexpr, TEST_ARGUMENTS.clone())
+        .expect("declaration file load failure");
+    Transformer::new().transform_avatar(decl_avatar)
+}
+
+fn compile_sexpr(source: &str) -> Avatar {
+    let avatar = transform_sexpr(source);
+    assert_eq!(avatar.logs, vec![]);
+    avatar.avatar.expect("avatar should be compiled")
+}
+
+fn compile_project_variant(profile: Option<&str>) -> Avatar {
+    let project = Project::find(&*TEST_PROJECT_SOURCE).expect("manifest should be found");
+    let mut args = Arguments::new();
+    project
+        .configure_arguments(&mut args, profile)
+        .expect("project should be applied");
+    let source = read_to_string(&*TEST_PROJECT_SOURCE).expect("source file should exist");
+    let decl_avatar =
+        compile_declaration(&source, DeclarationFormat::Sexpr, args).expect("declaration file load failure");
+
+    let mut transformer = Transformer::new();
+    project
+        .register_attachment_schemas(&mut transformer)
+        .expect("failed to load schemas");
+    let avatar = transformer.transform_avatar(decl_avatar);
+
+    assert_eq!(avatar.logs, vec![]);
+    avatar.avatar.expect("avatar should be compiled")
+}
+
 #[rstest]
 fn compiles_all_sexpr_examples(#[files("../examples/sexpr/*.declisp")] filename: PathBuf) {
     let source = read_to_string(&filename).expect("source file should exist");
@@ -35,11 +89,401 @@ fn compiles_all_sexpr_examples(#[files("../examples/sexpr/*.declisp")] filename:
     println!("compiling {:?}", filename.canonicalize().unwrap());
     let decl_avatar = compile_declaration(&source, DeclarationFormat::Sexpr, TEST_ARGUMENTS.clone())
         .expect("declaration file load failure");
+    serde_json::to_string(&decl_avatar).expect("declaration should be serialized");
+
+    let mut transformer = Transformer::new();
+    transformer
+        .load_arbittach_schemas(&*TEST_ARBITTACH_DIR)
+        .expect("failed to load schemas");
+    let avatar = transformer.transform_avatar(decl_avatar);
+
+    assert_eq!(avatar.logs, vec![]);
+    let avatar = avatar.avatar.expect("avatar should be compiled");
+
+    // skipped fields are lost, so compared in serialized form
+    let json = serde_json::to_value(&avatar).expect("avatar should be serialized");
+    let deserialized: Avatar = serde_json::from_value(json.clone()).expect("avatar should be deserialized");
+    let reserialized = serde_json::to_value(&deserialized).expect("avatar should be serialized");
+    assert_eq!(reserialized, json);
+}
+
+#[test]
+fn matches_checked_in_avatar_schema() {
+    // regenerate with `declavalidate schema -i > declavatar/schema/avatar.schema.json`
+    let checked_in: serde_json::Value =
+        serde_json::from_str(include_str!("../schema/avatar.schema.json")).expect("failed to deserialize");
+    let generated = serde_json::to_value(Avatar::json_schema()).expect("schema should be serialized");
+    assert_eq!(generated, checked_in);
+}
+
+#[test]
+fn rejects_conflicting_arbittach_versions() {
+    let mut transformer = Transformer::new();
+    transformer
+        .load_arbittach_schemas(&*TEST_ARBITTACH_DIR)
+        .expect("failed to load schemas");
+
+    // identical schema can be registered again
+    transformer
+        .register_arbittach_schema(TEST_ARBITTACH.clone())
+        .expect("identical schema should be accepted");
+
+    let mut older = TEST_ARBITTACH.clone();
+    older.version -= 1;
+    assert!(matches!(
+        transformer.register_arbittach_schema(older),
+        Err(SchemaError::VersionConflict { .. })
+    ));
+}
+
+#[test]
+fn fills_omitted_arbittach_parameters() {
+    let schema = |mode_default: &str| -> Attachment {
+        serde_json::from_value(serde_json::json!({
+            "name": "Light",
+            "properties": [{
+                "name": "Color",
+                "required": true,
+                "parameters": [
+                    { "name": "color", "value_type": { "type": "Vector", "content": 3 } },
+                    {
+                        "name": "mode",
+                        "value_type": { "type": "Enum", "content": ["additive", "multiply"] },
+                        "default": { "type": "String", "content": mode_default },
+                    },
+                    { "name": "label", "value_type": { "type": "String", "content": null }, "optional": true },
+                ],
+                "keywords": [{
+                    "name": "intensity",
+                    "required": false,
+                    "value_type": { "type": "Float", "content": null },
+                    "default": { "type": "Float", "content": 1.0 },
+                }],
+            }],
+        }))
+        .expect("failed to deserialize")
+    };
 
     let mut transformer = Transformer::new();
-    transformer.register_arbittach_schema(TEST_ARBITTACH.clone());
+    assert!(matches!(
+        transformer.register_arbittach_schema(schema("overlay")),
+        Err(SchemaError::InvalidDefault { item, .. }) if item == "mode"
+    ));
+    let mut misordered = schema("additive");
+    misordered.properties[0].parameters.swap(0, 2);
+    assert!(matches!(
+        transformer.register_arbittach_schema(misordered),
+        Err(SchemaError::RequiredAfterOmittable { parameter, .. }) if parameter == "color"
+    ));
+    transformer
+        .register_arbittach_schema(schema("additive"))
+        .expect("schema should be accepted");
+
+    let compile = |properties: &str| {
+        let source = format!(
+            r#"
+            (use da :self)
+            (use da3 :self)
+            (da/avatar "light"
+                (da3/attachments (da3/attachment "Light" {properties}))
+            )
+            "#
+        );
+        let decl_avatar = compile_declaration(&source, DeclarationFormat::Sexpr, TEST_ARGUMENTS.clone())
+            .expect("declaration file load failure");
+        transformer.transform_avatar(decl_avatar)
+    };
+    let color = Value::Vector(vec![1.0, 0.5, 0.0]);
+
+    // defaults are filled, but optional trailing parameters are left omitted
+    let avatar = compile(r#"(da3/property "Color" (da3/vector3 1.0 0.5 0.0))"#);
+    assert_eq!(avatar.logs, vec![]);
+    let avatar = avatar.avatar.expect("avatar should be compiled");
+    let property = &avatar.attachments[0].properties[0];
+    assert_eq!(
+        property.parameters,
+        vec![color.clone(), Value::String("additive".to_string())]
+    );
+    assert_eq!(property.keywords["intensity"], Value::Float(1.0));
+
+    let avatar = compile(r#"(da3/property "Color" (da3/vector3 1.0 0.5 0.0) "multiply" "lamp" :intensity 0.5)"#);
+    assert_eq!(avatar.logs, vec![]);
+    let avatar = avatar.avatar.expect("avatar should be compiled");
+    let property = &avatar.attachments[0].properties[0];
+    assert_eq!(
+        property.parameters,
+        vec![
+            color,
+            Value::String("multiply".to_string()),
+            Value::String("lamp".to_string())
+        ]
+    );
+    assert_eq!(property.keywords["intensity"], Value::Float(0.5));
+
+    let avatar = compile(r#"(da3/property "Color" (da3/vector3 1.0 0.5 0.0) "overlay")"#);
+    assert_eq!(avatar.logs.len(), 1);
+    assert_eq!(avatar.logs[0].kind, "arbittach.invalid_data");
+    assert_eq!(avatar.logs[0].args, vec!["unknown enum value: overlay"]);
+}
+
+#[test]
+fn merges_module_examples() {
+    let examples_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../examples/modules");
+    let base_source = read_to_string(examples_dir.join("base.declisp")).expect("source file should exist");
+    let hat_source = read_to_string(examples_dir.join("hat.declisp")).expect("source file should exist");
+
+    let base = DeclarationSource {
+        name: "base.declisp",
+        text: &base_source,
+        format: DeclarationFormat::Sexpr,
+    };
+    let modules = [DeclarationSource {
+        name: "hat.declisp",
+        text: &hat_source,
+        format: DeclarationFormat::Sexpr,
+    }];
+    let decl_avatar =
+        compile_declarations(base, &modules, TEST_ARGUMENTS.clone()).expect("declaration file load failure");
+
+    let transformer = Transformer::new();
     let avatar = transformer.transform_avatar(decl_avatar);
 
     assert!(avatar.avatar.is_some());
     assert_eq!(avatar.logs, vec![]);
+
+    // identical parameters are shared, but layers are not; every conflicting module is reported
+    let duplicated_modules = [
+        modules[0],
+        DeclarationSource {
+            name: "hat-copy.declisp",
+            ..modules[0]
+        },
+        DeclarationSource {
+            name: "hat-copy-2.declisp",
+            ..modules[0]
+        },
+    ];
+    assert!(matches!(
+        compile_declarations(base, &duplicated_modules, TEST_ARGUMENTS.clone()),
+        Err(DeclError::ModuleConflict(sources, conflicts))
+            if sources == "hat-copy.declisp, hat-copy-2.declisp"
+                && conflicts == "hat-copy.declisp: layer 'Hat' (declared by 'hat' in hat.declisp); \
+                    hat-copy-2.declisp: layer 'Hat' (declared by 'hat' in hat.declisp)"
+    ));
+}
+
+#[rstest]
+#[case(None, false)]
+#[case(Some("pc"), true)]
+#[case(Some("quest"), false)]
+fn compiles_project_example(#[case] profile: Option<&str>, #[case] has_blush: bool) {
+    let project = Project::discover(&*TEST_PROJECT_SOURCE)
+        .expect("manifest should be loaded")
+        .expect("manifest should be found");
+    assert_eq!(project.entries(), vec![project.root().join("avatar.declisp")]);
+
+    let avatar = compile_project_variant(profile);
+    assert_eq!(avatar.name, "project");
+    assert_eq!(avatar.fx_controller[0].name, "Hat");
+    assert_eq!(avatar.parameters.iter().any(|p| p.name == "Blush"), has_blush);
+}
+
+#[test]
+fn reports_differences_between_variants() {
+    let pc = compile_project_variant(Some("pc"));
+    let quest = compile_project_variant(Some("quest"));
+    let report = VariantReport::analyze([("pc", &pc), ("quest", &quest)]);
+
+    // profiles only toggle the blush feature
+    assert_eq!(
+        report.parameters,
+        vec![VariantDifference {
+            name: "Blush".to_string(),
+            present_in: vec!["pc".to_string()],
+            modified: false,
+        }]
+    );
+}
+
+#[test]
+fn checks_ordered_condition_thresholds() {
+    let source = |conditions: &str| {
+        format!(
+            r#"
+            (use da :self)
+            (da/avatar "thresholds"
+                (da/parameters
+                    (da/enum "Mood" "neutral" "smile" "angry")
+                    (da/int "Level" :min 0 :max 10)
+                    (da/float "Blend" :min 0.0 :max 1.0))
+                (da/assets (da/animation "neutral"))
+                (da/fx-controller
+                    (da/raw-layer "Mood"
+                        :default "neutral"
+                        (da/state "neutral"
+                            (da/clip "neutral")
+                            (da/transition-to "neutral" {conditions}))))
+            )
+            "#
+        )
+    };
+    let compile = |conditions: &str| transform_sexpr(&source(conditions));
+
+    // thresholds just outside of the domain are common for ordered comparisons
+    let avatar = compile(r#"(da/cond-gt "Mood" -1) (da/cond-lt "Mood" 3) (da/cond-lt "Blend" 1.5)"#);
+    assert!(avatar.avatar.is_some());
+    assert_eq!(avatar.logs, vec![]);
+
+    // comparisons never satisfied are warned
+    let avatar = compile(r#"(da/cond-gt "Level" 10) (da/cond-lt "Blend" 0.0)"#);
+    assert!(avatar.avatar.is_some());
+    assert_eq!(avatar.logs.len(), 2);
+    assert_eq!(avatar.logs[0].kind, "parameter.value_out_of_range");
+    assert_eq!(avatar.logs[0].args, vec!["Level", "10"]);
+    assert_eq!(avatar.logs[1].kind, "parameter.value_out_of_range");
+    assert_eq!(avatar.logs[1].args, vec!["Blend", "0"]);
+
+    // equality against values outside of enum is still an error
+    let avatar = compile(r#"(da/cond-eq "Mood" 3)"#);
+    assert!(avatar.avatar.is_none());
+}
+
+#[test]
+fn rejects_unknown_project_profile() {
+    let project_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../examples/project");
+    let project = Project::find(&project_dir).expect("manifest should be found");
+
+    let mut args = Arguments::new();
+    assert!(matches!(
+        project.configure_arguments(&mut args, Some("mobile")),
+        Err(ProjectError::UnknownProfile(name)) if name == "mobile"
+    ));
+}
+
+#[test]
+fn loads_modules_from_resolver() {
+    let mut args = TEST_ARGUMENTS.clone();
+    args.set_module_resolver(Some(ModuleResolver::new(|name| {
+        let source = r#"
+            (use da :self)
+            (define (visibility-param name) (da/bool name :scope 'local))
+            (export (visibility-param))
+        "#;
+        (name == "virtual-lib").then(|| source.to_string())
+    })));
+
+    let source = r#"
+        (use da :self)
+        (use virtual-lib :self)
+        (da/avatar "resolver"
+            (da/parameters (virtual-lib/visibility-param "Visible"))
+        )
+    "#;
+    let decl_avatar =
+        compile_declaration(source, DeclarationFormat::Sexpr, args).expect("declaration file load failure");
+    assert_eq!(decl_avatar.parameters_blocks[0].parameters.len(), 1);
+
+    let avatar = Transformer::new().transform_avatar(decl_avatar);
+    assert!(avatar.avatar.is_some());
+    assert_eq!(avatar.logs, vec![]);
+}
+
+#[test]
+fn analyzes_parameter_cross_reference() {
+    let filename = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../examples/sexpr/drive-parameter.declisp");
+    let source = read_to_string(filename).expect("source file should exist");
+    let xref = CrossReference::analyze(&compile_sexpr(&source));
+    let float_locations: Vec<_> = xref.parameters["float-param"]
+        .drive_writers
+        .iter()
+        .map(|w| w.location.as_str())
+        .collect();
+    assert!(float_locations.contains(&"smile"));
+    assert!(float_locations.contains(&"angry"));
+}
+
+#[test]
+fn warns_conflicting_layer_writers() {
+    let source = |allow_overlap: &str| {
+        format!(
+            r#"
+            (use da :self)
+            (da/avatar "overlap"
+                (da/parameters (da/bool "Hat") (da/bool "Hood"))
+                (da/fx-controller
+                    (da/switch-layer "Hat" :driven-by "Hat" :default-mesh "Body"
+                        (da/option 'disabled)
+                        (da/option 'enabled (da/set-shape "hide_hair")))
+                    (da/switch-layer "Hood" :driven-by "Hood" :default-mesh "Body" {allow_overlap}
+                        (da/option 'disabled)
+                        (da/option 'enabled (da/set-shape "hide_hair")))
+                )
+            )
+            "#
+        )
+    };
+
+    let avatar = transform_sexpr(&source(""));
+    assert!(avatar.avatar.is_some());
+    assert_eq!(avatar.logs.len(), 1);
+    assert_eq!(avatar.logs[0].kind, "layer.conflicting_writers");
+    assert_eq!(avatar.logs[0].args, vec!["shape://Body/hide_hair", "Hat < Hood"]);
+
+    // explicitly allowed overlaps compile without logs
+    compile_sexpr(&source(r#":allow-overlap "shape://Body/hide_hair""#));
+
+    // keys not animated by the layer are likely typos
+    let avatar = transform_sexpr(&source(r#":allow-overlap "shape://Body/hide-hair""#));
+    assert!(avatar.avatar.is_some());
+    assert_eq!(avatar.logs.len(), 2);
+    assert_eq!(avatar.logs[0].kind, "layer.unknown_overlap");
+    assert_eq!(avatar.logs[0].args, vec!["Hood", "shape://Body/hide-hair"]);
+    assert_eq!(avatar.logs[1].kind, "layer.conflicting_writers");
+}
+
+#[test]
+fn diffs_compiled_avatars() {
+    let source = |parameters: &str, shape: &str| {
+        format!(
+            r#"
+            (use da :self)
+            (da/avatar "diff"
+                (da/parameters {parameters})
+                (da/fx-controller
+                    (da/switch-layer "Hat" :driven-by "Hat" :default-mesh "Body"
+                        (da/option 'disabled)
+                        (da/option 'enabled (da/set-shape "{shape}")))
+                )
+                (da/menu (da/toggle "Hat" (da/drive-switch "Hat")))
+            )
+            "#
+        )
+    };
+
+    let old = compile_sexpr(&source(r#"(da/bool "Hat" :scope 'local)"#, "hide_hair"));
+    let new = compile_sexpr(&source(r#"(da/bool "Hat") (da/int "Emote")"#, "hide_bangs"));
+    assert!(AvatarDiff::compare(&old, &old).is_empty());
+
+    // compiled targets are compared by their driving keys
+    let diff = AvatarDiff::compare(&old, &new);
+    assert_eq!(diff.menu_items, vec![]);
+    assert_eq!(
+        diff.layers,
+        vec![LayerChange::Modified {
+            name: "Hat".to_string(),
+            kind: None,
+            parameter: None,
+            options: vec![OptionChange::Modified {
+                name: "enabled".to_string(),
+                index: None,
+                settings_changed: false,
+                targets: vec![
+                    TargetChange::Removed("shape://Body/hide_hair".to_string()),
+                    TargetChange::Added("shape://Body/hide_bangs".to_string()),
+                ],
+            }],
+            transitions_changed: false,
+        }]
+    );
+    assert_eq!((diff.synced_bits.old, diff.synced_bits.new), (0, 9));
 }